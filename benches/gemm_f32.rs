@@ -36,6 +36,47 @@ fn bench_gemm(criterion: &mut Criterion) {
     bench_kernel_with(group, "generic-kernel-8x8", kernel, mkn, pack_sizes);
 }
 
+fn bench_small_gemm(criterion: &mut Criterion) {
+    let group = &mut criterion.benchmark_group("bench-small-gemm-f32");
+
+    for dim in [1, 2, 3, 4, 8, 16, 32, 64] {
+        let mkn = [dim; 3];
+        let pack_sizes = PackSizes {
+            mc: 64,
+            kc: 64,
+            nc: 64,
+        };
+
+        #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+        {
+            use microgemm::kernels::NeonKernel8x8;
+
+            let kernel = unsafe { &NeonKernel8x8::<f32>::new() };
+            let name = format!("neon-kernel-8x8/{dim}");
+            bench_kernel_with(group, &name, kernel, mkn, pack_sizes);
+        }
+
+        #[cfg(target_arch = "x86_64")]
+        {
+            use microgemm::kernels::{Avx2FmaKernel8x8, Sse2Kernel4x4};
+
+            if is_x86_feature_detected!("avx2") && is_x86_feature_detected!("fma") {
+                let kernel = unsafe { &Avx2FmaKernel8x8::<f32>::new() };
+                let name = format!("avx2-fma-kernel-8x8/{dim}");
+                bench_kernel_with(group, &name, kernel, mkn, pack_sizes);
+            }
+
+            let kernel = &Sse2Kernel4x4::<f32>::new();
+            let name = format!("sse2-kernel-4x4/{dim}");
+            bench_kernel_with(group, &name, kernel, mkn, pack_sizes);
+        }
+
+        let kernel = &GenericKernel8x8::<f32>::new();
+        let name = format!("generic-kernel-8x8/{dim}");
+        bench_kernel_with(group, &name, kernel, mkn, pack_sizes);
+    }
+}
+
 fn bench_kernel_with(
     group: &mut BenchmarkGroup<'_, WallTime>,
     name: &str,
@@ -72,5 +113,5 @@ fn matrices<'a, T>(
     (a, b, c)
 }
 
-criterion_group!(benches, bench_gemm);
+criterion_group!(benches, bench_gemm, bench_small_gemm);
 criterion_main!(benches);
@@ -0,0 +1,38 @@
+use microgemm::{autotune, kernels::GenericKernel8x8, CacheInfo, Kernel as _};
+
+type Tuned = GenericKernel8x8<f32>;
+
+const REPEATS: usize = 3;
+
+// cargo run --release --features std --example autotune -- [m k n]
+fn main() {
+    let args = std::env::args()
+        .skip(1)
+        .map(|arg| arg.parse().expect("dimensions must be integers"))
+        .collect::<Vec<usize>>();
+    let shape = match args[..] {
+        [] => [256; 3],
+        [m, k, n] => [m, k, n],
+        _ => panic!("expected no arguments or `m k n`"),
+    };
+
+    let cache = CacheInfo::detect().unwrap_or(CacheInfo {
+        l1: 32 << 10,
+        l2: 256 << 10,
+        l3: 0,
+    });
+    println!("{cache:?}");
+
+    let kernel = Tuned::new();
+    println!("{}x{} kernel, [m, k, n] = {shape:?}", Tuned::MR, Tuned::NR);
+    let grid = autotune::grid::<Tuned>(cache, shape);
+    let measurements = autotune::autotune(&kernel, shape, &grid, REPEATS);
+
+    println!("{:>6} {:>6} {:>6} {:>10}", "mc", "kc", "nc", "GFLOP/s");
+    for m in measurements.iter().rev() {
+        let p = m.pack_sizes;
+        println!("{:>6} {:>6} {:>6} {:>10.2}", p.mc, p.kc, p.nc, m.gflops);
+    }
+    println!();
+    print!("{}", autotune::to_rust_consts(measurements[0].pack_sizes));
+}
@@ -24,4 +24,51 @@ fn main() {
     let (alpha, beta) = (f16::ONE, f16::ZERO);
     kernel.gemm(alpha, a, b, beta, &mut c, pack_sizes, &mut packing_buf);
     println!("{:?}", c.as_slice());
+
+    // f16 inputs with f32 accumulation and output
+    let mut c = [0f32; 2 * 2];
+    let mut c = MatMut::row_major(2, 2, c.as_mut());
+
+    let kernel = GenericKernel2x2::<f32>::new();
+    let mut packing_buf = vec![0f32; pack_sizes.buf_len()];
+    kernel.gemm_mixed(1.0, a, b, 0.0, &mut c, pack_sizes, &mut packing_buf);
+    println!("{:?}", c.as_slice());
+
+    // for a large k, the sum of f16 products loses precision in f16
+    let k = 10_000;
+    let a = vec![f16::from_f32(0.1); k];
+    let a = MatRef::row_major(1, k, &a);
+    let b = vec![f16::ONE; k];
+    let b = MatRef::row_major(k, 1, &b);
+    let pack_sizes = PackSizes {
+        mc: 2,
+        kc: 256,
+        nc: 2,
+    };
+    let expect = k as f32 * f16::from_f32(0.1).to_f32();
+
+    let mut c16 = [f16::ZERO];
+    let mut packing_buf = vec![f16::ZERO; pack_sizes.buf_len()];
+    GenericKernel2x2::<f16>::new().gemm(
+        f16::ONE,
+        a,
+        b,
+        f16::ZERO,
+        &mut MatMut::row_major(1, 1, &mut c16),
+        pack_sizes,
+        &mut packing_buf,
+    );
+
+    let mut c32 = [0f32];
+    let mut packing_buf = vec![0f32; pack_sizes.buf_len()];
+    kernel.gemm_mixed(
+        1.0,
+        a,
+        b,
+        0.0,
+        &mut MatMut::row_major(1, 1, &mut c32),
+        pack_sizes,
+        &mut packing_buf,
+    );
+    println!("k = {k}: expected {expect}, f16 accumulation {c16:?}, f32 accumulation {c32:?}");
 }
@@ -0,0 +1,171 @@
+//! Empirical search of [`PackSizes`] by timing [`Kernel::gemm`] on the local machine.
+//!
+//! # Examples
+//!
+//! ```
+//! use microgemm::{autotune, kernels::GenericKernel4x4, CacheInfo, PackSizes};
+//!
+//! let kernel = GenericKernel4x4::<f32>::new();
+//! let cache = CacheInfo { l1: 32 << 10, l2: 256 << 10, l3: 0 };
+//! let shape = [32, 32, 32];
+//!
+//! let grid = autotune::grid::<GenericKernel4x4<f32>>(cache, shape);
+//! let measurements = autotune::autotune(&kernel, shape, &grid, 1);
+//! let best = measurements[0].pack_sizes;
+//! println!("{}", autotune::to_rust_consts(best));
+//! ```
+
+use crate::{CacheInfo, Kernel, MatMut, MatRef, PackSizes};
+use num_traits::{One, Zero};
+use std::{format, string::String, time::Instant, vec::Vec};
+
+/// Timing of [`Kernel::gemm`] with one configuration.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Measurement {
+    pub pack_sizes: PackSizes,
+    /// Best time of a single gemm in seconds.
+    pub seconds: f64,
+    /// `2 m k n` floating point operations per second, in billions.
+    pub gflops: f64,
+}
+
+/// Candidate `PackSizes` for an `m x k` by `k x n` product:
+/// the sizes of [`PackSizes::for_kernel`] scaled by `1/4`, `1/2`, `1` and `2` in every dimension,
+/// clamped to the shape of the problem and deduplicated.
+pub fn grid<K: Kernel + ?Sized>(cache: CacheInfo, [m, k, n]: [usize; 3]) -> Vec<PackSizes> {
+    let [mr, nr] = [K::MR, K::NR];
+    let seed = PackSizes::for_kernel::<K>(cache);
+    let scaled = |x: usize, r: usize, max: usize| {
+        let max = ((max + r - 1) / r).max(1) * r;
+        [x / 4, x / 2, x, 2 * x].map(|x| (x / r * r).clamp(r, max))
+    };
+
+    let mut grid = Vec::new();
+    for mc in scaled(seed.mc, mr, m) {
+        for kc in scaled(seed.kc, 1, k) {
+            for nc in scaled(seed.nc, nr, n) {
+                let pack_sizes = PackSizes { mc, kc, nc };
+                if !grid.contains(&pack_sizes) {
+                    grid.push(pack_sizes);
+                }
+            }
+        }
+    }
+    grid
+}
+
+/// Times `kernel.gemm` for an `m x k` by `k x n` product with every candidate,
+/// keeping the best of `repeats` runs, and returns the measurements from the fastest one.
+///
+/// # Panics
+/// If a candidate is too small for the kernel or `repeats` is zero.
+pub fn autotune<K>(
+    kernel: &K,
+    [m, k, n]: [usize; 3],
+    candidates: &[PackSizes],
+    repeats: usize,
+) -> Vec<Measurement>
+where
+    K: Kernel + ?Sized,
+{
+    assert!(repeats > 0);
+    let one = K::Scalar::one();
+    let a = vec![one; m * k];
+    let b = vec![one; k * n];
+    let mut c = vec![K::Scalar::zero(); m * n];
+    let a = MatRef::row_major(m, k, &a);
+    let b = MatRef::row_major(k, n, &b);
+    let mut c = MatMut::col_major(m, n, &mut c);
+
+    let flops = 2.0 * m as f64 * k as f64 * n as f64;
+    let mut measurements = candidates
+        .iter()
+        .map(|&pack_sizes| {
+            let mut packing_buf = vec![K::Scalar::zero(); pack_sizes.buf_len()];
+            let seconds = (0..repeats)
+                .map(|_| {
+                    let start = Instant::now();
+                    kernel.gemm(one, a, b, one, &mut c, pack_sizes, &mut packing_buf);
+                    start.elapsed().as_secs_f64()
+                })
+                .fold(f64::INFINITY, f64::min);
+            Measurement {
+                pack_sizes,
+                seconds,
+                gflops: flops / seconds / 1e9,
+            }
+        })
+        .collect::<Vec<_>>();
+    measurements.sort_by(|x, y| x.seconds.total_cmp(&y.seconds));
+    measurements
+}
+
+/// Formats `pack_sizes` as Rust `const` items, like `benches/cache.py`.
+///
+/// # Examples
+///
+/// ```
+/// use microgemm::{autotune::to_rust_consts, PackSizes};
+///
+/// let code = to_rust_consts(PackSizes { mc: 64, kc: 256, nc: 512 });
+/// assert_eq!(code, "const MC: usize = 64;\nconst KC: usize = 256;\nconst NC: usize = 512;\n");
+/// ```
+pub fn to_rust_consts(pack_sizes: PackSizes) -> String {
+    let PackSizes { mc, kc, nc } = pack_sizes;
+    format!("const MC: usize = {mc};\nconst KC: usize = {kc};\nconst NC: usize = {nc};\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kernels::GenericKernel4x4;
+
+    #[test]
+    fn test_grid() {
+        let cache = CacheInfo {
+            l1: 32 << 10,
+            l2: 256 << 10,
+            l3: 0,
+        };
+        let small = grid::<GenericKernel4x4<f32>>(cache, [10, 3, 1]);
+        // every scale of the seed is clamped to the shape
+        assert_eq!(
+            small,
+            [PackSizes {
+                mc: 12,
+                kc: 3,
+                nc: 4
+            }]
+        );
+
+        let large = grid::<GenericKernel4x4<f32>>(cache, [1000, 1000, 1000]);
+        assert_eq!(large.len(), 4 * 4 * 4);
+        let kernel = GenericKernel4x4::<f32>::new();
+        for pack_sizes in large {
+            assert_eq!(pack_sizes.mc % 4, 0);
+            assert_eq!(pack_sizes.nc % 4, 0);
+            let _ = pack_sizes.packed_b_len(&kernel, 1, 1);
+        }
+    }
+
+    #[test]
+    fn test_autotune() {
+        let kernel = GenericKernel4x4::<f32>::new();
+        let candidates = [
+            PackSizes {
+                mc: 4,
+                kc: 1,
+                nc: 4,
+            },
+            PackSizes {
+                mc: 8,
+                kc: 8,
+                nc: 8,
+            },
+        ];
+        let measurements = autotune(&kernel, [8, 8, 8], &candidates, 2);
+        assert_eq!(measurements.len(), 2);
+        assert!(measurements[0].seconds <= measurements[1].seconds);
+        assert!(measurements.iter().all(|x| x.gflops > 0.0));
+    }
+}
@@ -0,0 +1,506 @@
+use crate::gemm::{check_dims, check_gemm_args, check_pack_sizes, gemm_loops, gemm_packing};
+use crate::kernel::Multiply;
+use crate::packing::Packer;
+use crate::{Epilogue, GemmError, Kernel, MatMut, MatRef, PackSizes, PackedB};
+use generic_array::{sequence::GenericSequence, GenericArray};
+use num_traits::{One, Zero};
+
+type Product<L, R> = <L as Multiply<R>>::Output;
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn try_gemm_batched<T, K>(
+    kernel: &K,
+    alpha: T,
+    a: &[MatRef<T>],
+    b: &[MatRef<T>],
+    beta: T,
+    c: &mut [MatMut<T>],
+    pack_sizes: PackSizes,
+    packing_buf: &mut [T],
+) -> Result<(), GemmError>
+where
+    T: Copy + Zero + One,
+    K: Kernel<Scalar = T> + ?Sized,
+{
+    if a.len() != b.len() || b.len() != c.len() {
+        return Err(GemmError::BatchLen {
+            a: a.len(),
+            b: b.len(),
+            c: c.len(),
+        });
+    }
+    let [mr, nr] = [K::MR, K::NR];
+    let shared_b = shared_b(b);
+    // a shared `b` may come with a buffer to pack it whole, see `PackSizes::batched_buf_len`
+    let buf_len = match shared_b {
+        Some(b) if is_batched_buf_len(nr, pack_sizes, b, packing_buf.len()) => {
+            pack_sizes.checked_buf_len().unwrap_or_default()
+        }
+        _ => packing_buf.len(),
+    };
+    let clamped = check_pack_sizes(mr, nr, pack_sizes, buf_len)?;
+    for ((&a, &b), c) in a.iter().zip(b).zip(c.iter()) {
+        check_dims(a, b, c.to_ref())?;
+    }
+
+    let zero = T::zero();
+    let mut dst_buf = GenericArray::<T, Product<K::Mr, K::Nr>>::generate(|_| zero);
+    let dst_buf = dst_buf.as_mut_slice();
+
+    let (apack, bpack) = packing_buf.split_at_mut(pack_sizes.mc * pack_sizes.kc);
+    if let Some(b) = shared_b.and_then(|&b| pack_shared_b(kernel, b, pack_sizes, bpack)) {
+        for (&a, c) in a.iter().zip(c) {
+            gemm_shared_b(kernel, alpha, a, &b, beta, c, clamped, apack, dst_buf);
+        }
+        return Ok(());
+    }
+    let pack_sizes = clamped;
+    let packing_buf = &mut packing_buf[..pack_sizes.buf_len()];
+    for ((&a, &b), c) in a.iter().zip(b).zip(c) {
+        gemm_item(
+            kernel,
+            alpha,
+            a,
+            b,
+            beta,
+            c,
+            pack_sizes,
+            packing_buf,
+            dst_buf,
+        );
+    }
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn try_gemm_strided_batched<T, K>(
+    kernel: &K,
+    batch_count: usize,
+    alpha: T,
+    a: MatRef<T>,
+    stride_a: usize,
+    b: MatRef<T>,
+    stride_b: usize,
+    beta: T,
+    c: &mut MatMut<T>,
+    stride_c: usize,
+    pack_sizes: PackSizes,
+    packing_buf: &mut [T],
+) -> Result<(), GemmError>
+where
+    T: Copy + Zero + One,
+    K: Kernel<Scalar = T> + ?Sized,
+{
+    let [mr, nr] = [K::MR, K::NR];
+    let broadcast_b = stride_b == 0 || batch_count == 1;
+    // a broadcast `b` may come with a buffer to pack it whole, see `PackSizes::batched_buf_len`
+    let buf_len = if broadcast_b && is_batched_buf_len(nr, pack_sizes, &b, packing_buf.len()) {
+        pack_sizes.checked_buf_len().unwrap_or_default()
+    } else {
+        packing_buf.len()
+    };
+    let clamped = check_gemm_args(mr, nr, a, b, c.to_ref(), pack_sizes, buf_len)?;
+    if batch_count == 0 {
+        return Ok(());
+    }
+    check_batch_bounds(batch_count, a.span(), stride_a, a.as_slice().len())?;
+    check_batch_bounds(batch_count, b.span(), stride_b, b.as_slice().len())?;
+    check_batch_bounds(batch_count, c.span(), stride_c, c.as_slice().len())?;
+    if batch_count > 1 && stride_c < c.span() {
+        // the matrices of c would overlap
+        return Err(GemmError::BatchStride);
+    }
+
+    let zero = T::zero();
+    let mut dst_buf = GenericArray::<T, Product<K::Mr, K::Nr>>::generate(|_| zero);
+    let dst_buf = dst_buf.as_mut_slice();
+
+    let (apack, bpack) = packing_buf.split_at_mut(pack_sizes.mc * pack_sizes.kc);
+    let shared_b = broadcast_b.then_some(b);
+    if let Some(b) = shared_b.and_then(|b| pack_shared_b(kernel, b, pack_sizes, bpack)) {
+        for i in 0..batch_count {
+            let a = a.offset_by(i * stride_a);
+            let c = &mut c.offset_by_mut(i * stride_c);
+            gemm_shared_b(kernel, alpha, a, &b, beta, c, clamped, apack, dst_buf);
+        }
+        return Ok(());
+    }
+    let pack_sizes = clamped;
+    let packing_buf = &mut packing_buf[..pack_sizes.buf_len()];
+    for i in 0..batch_count {
+        let [a, b] = [a.offset_by(i * stride_a), b.offset_by(i * stride_b)];
+        let c = &mut c.offset_by_mut(i * stride_c);
+        gemm_item(
+            kernel,
+            alpha,
+            a,
+            b,
+            beta,
+            c,
+            pack_sizes,
+            packing_buf,
+            dst_buf,
+        );
+    }
+    Ok(())
+}
+
+// Checks that the last of `batch_count` matrices of `span` values is in `len` values.
+fn check_batch_bounds(
+    batch_count: usize,
+    span: usize,
+    stride: usize,
+    len: usize,
+) -> Result<(), GemmError> {
+    let end = (batch_count - 1)
+        .checked_mul(stride)
+        .and_then(|offset| offset.checked_add(span))
+        .ok_or(GemmError::Overflow)?;
+    if end > len {
+        return Err(GemmError::BatchStride);
+    }
+    Ok(())
+}
+
+// c <- alpha a b + beta c for checked arguments and clamped `pack_sizes`.
+#[allow(clippy::too_many_arguments)]
+fn gemm_item<T, K>(
+    kernel: &K,
+    alpha: T,
+    a: MatRef<T>,
+    b: MatRef<T>,
+    beta: T,
+    c: &mut MatMut<T>,
+    pack_sizes: PackSizes,
+    packing_buf: &mut [T],
+    dst_buf: &mut [T],
+) where
+    T: Copy + Zero + One,
+    K: Kernel<Scalar = T> + ?Sized,
+{
+    let epilogue = None::<&mut dyn Epilogue<T>>;
+    gemm_packing(
+        kernel,
+        alpha,
+        a,
+        b,
+        beta,
+        c,
+        pack_sizes,
+        packing_buf,
+        dst_buf,
+        epilogue,
+    );
+}
+
+// Returns the common `b` if all of them are the same view of the same values.
+fn shared_b<'a, 'b, T>(b: &'a [MatRef<'b, T>]) -> Option<&'a MatRef<'b, T>> {
+    let (first, rest) = b.split_first()?;
+    let same = |b: &MatRef<T>| {
+        b.as_ptr() == first.as_ptr()
+            && b.as_slice().len() == first.as_slice().len()
+            && [b.nrows(), b.ncols()] == [first.nrows(), first.ncols()]
+            && [b.row_stride(), b.col_stride()] == [first.row_stride(), first.col_stride()]
+    };
+    if rest.iter().all(same) {
+        Some(first)
+    } else {
+        None
+    }
+}
+
+// Whether `len` is the `batched_buf_len` of `b` for valid `pack_sizes`.
+fn is_batched_buf_len<T>(nr: usize, pack_sizes: PackSizes, b: &MatRef<T>, len: usize) -> bool {
+    pack_sizes.nc >= nr
+        && pack_sizes.kc > 0
+        && pack_sizes.checked_batched_buf_len(nr, b.nrows(), b.ncols()) == Some(len)
+}
+
+// Packs `b`, shared by the whole batch, once into `bpack` if it is long enough.
+fn pack_shared_b<'a, T, K>(
+    kernel: &K,
+    b: MatRef<T>,
+    pack_sizes: PackSizes,
+    bpack: &'a mut [T],
+) -> Option<PackedB<&'a mut [T], T>>
+where
+    T: Copy + Zero,
+    K: Kernel<Scalar = T> + ?Sized,
+{
+    let len = pack_sizes.checked_packed_b_len(K::NR, b.nrows(), b.ncols())?;
+    let bpack = bpack.get_mut(..len)?;
+    Some(PackedB::pack_into(kernel, b, pack_sizes, bpack))
+}
+
+// c <- alpha a b + beta c for `b` packed by `pack_shared_b`.
+#[allow(clippy::too_many_arguments)]
+fn gemm_shared_b<T, K>(
+    kernel: &K,
+    alpha: T,
+    a: MatRef<T>,
+    b: &PackedB<&mut [T], T>,
+    beta: T,
+    c: &mut MatMut<T>,
+    pack_sizes: PackSizes,
+    apack: &mut [T],
+    dst_buf: &mut [T],
+) where
+    T: Copy + Zero + One,
+    K: Kernel<Scalar = T> + ?Sized,
+{
+    let epilogue = None::<&mut dyn Epilogue<T>>;
+    gemm_loops(
+        kernel,
+        alpha,
+        &mut Packer::new(a, apack),
+        &mut &*b,
+        beta,
+        c,
+        a.ncols(),
+        pack_sizes,
+        dst_buf,
+        epilogue,
+    );
+}
+
+#[cfg(not(miri))]
+#[cfg(test)]
+mod proptests {
+    use super::*;
+    use crate::mat::base::MatBase;
+    use crate::{
+        as_mut,
+        kernels::GenericKernel4x4,
+        std_prelude::*,
+        utils::{arb_matrix_triple_with, naive_gemm},
+    };
+    use proptest::prelude::*;
+
+    fn to_mut(mat: &mut MatBase<Vec<i32>, i32>) -> MatMut<'_, i32> {
+        let [nrows, ncols] = [mat.nrows(), mat.ncols()];
+        let [row_stride, col_stride] = [mat.row_stride(), mat.col_stride()];
+        MatMut::from_parts(nrows, ncols, mat.as_mut_slice(), row_stride, col_stride).unwrap()
+    }
+
+    proptest! {
+        #[test]
+        fn proptest_gemm_batched(
+            batch in prop::collection::vec(
+                arb_matrix_triple_with(0..20, 0..20, 0..20, -10i32..10), 0..5
+            ),
+            alpha in -10i32..10,
+            beta in -10i32..10,
+            [mc, kc, nc] in [1..4usize, 1..20, 1..4],
+        ) {
+            let kernel = GenericKernel4x4::new();
+            let pack_sizes = PackSizes { mc: mc * kernel.mr(), kc, nc: nc * kernel.nr() };
+            let mut packing_buf = vec![-1; pack_sizes.buf_len()];
+
+            let mut expect = batch.iter().map(|[_, _, c]| c.clone()).collect::<Vec<_>>();
+            for ([a, b, _], c) in batch.iter().zip(expect.iter_mut()) {
+                naive_gemm(alpha, a.to_ref(), b.to_ref(), beta, as_mut!(c));
+            }
+
+            let mut actual = batch.iter().map(|[_, _, c]| c.clone()).collect::<Vec<_>>();
+            let a = batch.iter().map(|[a, _, _]| a.to_ref()).collect::<Vec<_>>();
+            let b = batch.iter().map(|[_, b, _]| b.to_ref()).collect::<Vec<_>>();
+            let mut c = actual.iter_mut().map(to_mut).collect::<Vec<_>>();
+            kernel.gemm_batched(alpha, &a, &b, beta, &mut c, pack_sizes, &mut packing_buf);
+            for (expect, actual) in expect.iter().zip(&actual) {
+                prop_assert_eq!(expect.as_slice(), actual.as_slice());
+            }
+        }
+
+        #[test]
+        fn proptest_gemm_batched_shared_b(
+            [a, b, c] in arb_matrix_triple_with(0..20, 0..20, 0..20, -10i32..10),
+            alpha in -10i32..10,
+            beta in -10i32..10,
+            [mc, kc, nc] in [1..4usize, 1..40, 1..8],
+            batch_len in 0..4usize,
+        ) {
+            let kernel = GenericKernel4x4::new();
+            let pack_sizes = PackSizes { mc: mc * kernel.mr(), kc, nc: nc * kernel.nr() };
+            let mut packing_buf = vec![-1; pack_sizes.buf_len()];
+
+            let mut expect = c.clone();
+            naive_gemm(alpha, a.to_ref(), b.to_ref(), beta, as_mut!(expect));
+
+            let mut actual = vec![c.clone(); batch_len];
+            let a = vec![a.to_ref(); batch_len];
+            let b = vec![b.to_ref(); batch_len];
+            let mut c_mats = actual.iter_mut().map(to_mut).collect::<Vec<_>>();
+            kernel.gemm_batched(alpha, &a, &b, beta, &mut c_mats, pack_sizes, &mut packing_buf);
+            for actual in &actual {
+                prop_assert_eq!(expect.as_slice(), actual.as_slice());
+            }
+
+            // with room for the whole packed b
+            let [k, n] = [b.first().map_or(0, |b| b.nrows()), b.first().map_or(0, |b| b.ncols())];
+            let mut packing_buf = vec![-1; pack_sizes.batched_buf_len(&kernel, k, n)];
+            let mut actual = vec![c; batch_len];
+            let mut c = actual.iter_mut().map(to_mut).collect::<Vec<_>>();
+            kernel.gemm_batched(alpha, &a, &b, beta, &mut c, pack_sizes, &mut packing_buf);
+            for actual in &actual {
+                prop_assert_eq!(expect.as_slice(), actual.as_slice());
+            }
+        }
+
+        #[test]
+        fn proptest_gemm_strided_batched(
+            [m, k, n] in [0..12usize, 0..12, 0..12],
+            batch_count in 0..4usize,
+            [broadcast_a, broadcast_b, whole_b] in [any::<bool>(), any::<bool>(), any::<bool>()],
+            alpha in -10i32..10,
+            beta in -10i32..10,
+            [mc, kc, nc] in [1..4usize, 1..20, 1..4],
+            seed in any::<i32>(),
+        ) {
+            let kernel = GenericKernel4x4::new();
+            let pack_sizes = PackSizes { mc: mc * kernel.mr(), kc, nc: nc * kernel.nr() };
+            let buf_len = if broadcast_b && whole_b {
+                pack_sizes.batched_buf_len(&kernel, k, n)
+            } else {
+                pack_sizes.buf_len()
+            };
+            let mut packing_buf = vec![-1; buf_len];
+
+            // each matrix of the batch is followed by a gap of one value
+            let stride = |len: usize, broadcast: bool| if broadcast { 0 } else { len + 1 };
+            let [stride_a, stride_b, stride_c] =
+                [stride(m * k, broadcast_a), stride(k * n, broadcast_b), stride(m * n, false)];
+            let values = |len: usize| {
+                (0..len).map(|x| (x as i32 ^ seed) % 10).collect::<Vec<_>>()
+            };
+            let a = values(batch_count * stride_a + m * k);
+            let b = values(batch_count * stride_b + k * n);
+            let c = values(batch_count * stride_c + m * n);
+
+            let mut expect = c.clone();
+            for i in 0..batch_count {
+                let a = MatRef::row_major(m, k, &a[i * stride_a..][..m * k]);
+                let b = MatRef::row_major(k, n, &b[i * stride_b..][..k * n]);
+                let mut c = MatMut::row_major(m, n, &mut expect[i * stride_c..][..m * n]);
+                naive_gemm(alpha, a, b, beta, &mut c);
+            }
+
+            let mut actual = c;
+            let a = MatRef::from_parts(m, k, &a[..], k, 1).unwrap();
+            let b = MatRef::from_parts(k, n, &b[..], n, 1).unwrap();
+            let mut c = MatMut::from_parts(m, n, &mut actual[..], n, 1).unwrap();
+            kernel.gemm_strided_batched(
+                batch_count, alpha, a, stride_a, b, stride_b, beta, &mut c, stride_c,
+                pack_sizes, &mut packing_buf,
+            );
+            prop_assert_eq!(expect, actual);
+        }
+    }
+
+    #[test]
+    fn test_gemm_batched_errors() {
+        let kernel = GenericKernel4x4::<i32>::new();
+        let pack_sizes = PackSizes {
+            mc: 4,
+            kc: 2,
+            nc: 4,
+        };
+        let mut packing_buf = vec![0; pack_sizes.buf_len()];
+        let a = MatRef::row_major(1, 1, &[1]);
+        let mut c = [[2], [3]];
+        let [c0, c1] = &mut c;
+        let mut c = [MatMut::row_major(1, 1, c0), MatMut::row_major(1, 1, c1)];
+
+        let result = try_gemm_batched(
+            &kernel,
+            1,
+            &[a],
+            &[a, a],
+            0,
+            &mut c,
+            pack_sizes,
+            &mut packing_buf,
+        );
+        assert_eq!(result, Err(GemmError::BatchLen { a: 1, b: 2, c: 2 }));
+
+        let b = MatRef::row_major(2, 1, &[1, 1]);
+        let result = try_gemm_batched(
+            &kernel,
+            1,
+            &[a, a],
+            &[a, b],
+            0,
+            &mut c,
+            pack_sizes,
+            &mut packing_buf,
+        );
+        let expect = GemmError::DimMismatch {
+            a: [1, 1],
+            b: [2, 1],
+            c: [1, 1],
+        };
+        assert_eq!(result, Err(expect));
+        assert_eq!(c[0].as_slice(), [2]);
+
+        // room for a whole packed b is only accepted if b is shared
+        let a = MatRef::row_major(1, 3, &[1, 2, 3]);
+        let [b0, b1] = [[1; 3 * 4], [2; 3 * 4]];
+        let [b0, b1] = [MatRef::row_major(3, 4, &b0), MatRef::row_major(3, 4, &b1)];
+        let mut c = [[0; 4], [0; 4]];
+        let [c0, c1] = &mut c;
+        let mut c = [MatMut::row_major(1, 4, c0), MatMut::row_major(1, 4, c1)];
+        let mut packing_buf = vec![0; pack_sizes.batched_buf_len(&kernel, 3, 4)];
+        let result = try_gemm_batched(
+            &kernel,
+            1,
+            &[a, a],
+            &[b0, b1],
+            0,
+            &mut c,
+            pack_sizes,
+            &mut packing_buf,
+        );
+        let expect = GemmError::BufferLen {
+            expected: pack_sizes.buf_len(),
+            got: pack_sizes.batched_buf_len(&kernel, 3, 4),
+        };
+        assert_eq!(result, Err(expect));
+    }
+
+    #[test]
+    fn test_gemm_strided_batched_errors() {
+        let kernel = GenericKernel4x4::<i32>::new();
+        let pack_sizes = PackSizes {
+            mc: 4,
+            kc: 2,
+            nc: 4,
+        };
+        let mut packing_buf = vec![0; pack_sizes.buf_len()];
+        let a = MatRef::row_major(1, 2, &[1, 2]);
+        let b = MatRef::row_major(2, 1, &[1, 2]);
+        let mut c = [0, 0];
+        let mut c = MatMut::from_parts(1, 1, &mut c[..], 1, 1).unwrap();
+
+        let mut gemm = |batch_count, stride_a, stride_c| {
+            try_gemm_strided_batched(
+                &kernel,
+                batch_count,
+                1,
+                a,
+                stride_a,
+                b,
+                0,
+                0,
+                &mut c,
+                stride_c,
+                pack_sizes,
+                &mut packing_buf,
+            )
+        };
+        assert_eq!(gemm(2, 0, 1), Ok(()));
+        assert_eq!(gemm(2, 1, 1), Err(GemmError::BatchStride));
+        assert_eq!(gemm(2, 0, 0), Err(GemmError::BatchStride));
+        assert_eq!(gemm(3, 0, 1), Err(GemmError::BatchStride));
+        assert_eq!(gemm(2, usize::MAX, 1), Err(GemmError::Overflow));
+        assert_eq!(c.as_slice(), [5, 5]);
+    }
+}
@@ -0,0 +1,329 @@
+use core::ops::Add;
+use num_traits::Zero;
+
+/// Elementwise operation fused into gemm, see [`Kernel::gemm_with_epilogue`].
+///
+/// [`apply`](Self::apply) is called exactly once for every element of `c`
+/// with the final value of `alpha a b + beta c`, after the last `kc` block of its tile,
+/// while the tile is still in registers. The order of the elements is unspecified.
+///
+/// Implemented for closures `FnMut(row, col, value) -> T`, see below for the ready-made ones.
+///
+/// # Limitations
+///
+/// The result is written back to `c`, so it has the scalar type of the kernel:
+/// an epilogue cannot write `c` in another type, e.g. quantize an `f32` result to `i8`.
+/// Convert `c` after gemm instead.
+///
+/// # Examples
+///
+/// ```
+/// use microgemm::{
+///     epilogue::{ColBias, Relu},
+///     kernels::GenericKernel2x2,
+///     Epilogue as _, Kernel as _, MatMut, MatRef, PackSizes,
+/// };
+///
+/// let kernel = GenericKernel2x2::<i32>::new();
+/// let pack_sizes = PackSizes { mc: 2, kc: 2, nc: 2 };
+/// let mut packing_buf = [0; PackSizes { mc: 2, kc: 2, nc: 2 }.buf_len()];
+///
+/// let a = MatRef::row_major(1, 2, &[1, 2]);
+/// let b = MatRef::row_major(2, 2, &[1, -1, 1, -1]);
+/// let mut c = [0; 2];
+/// let mut c = MatMut::row_major(1, 2, &mut c);
+///
+/// // c <- relu(a b + bias)
+/// let epilogue = ColBias(&[1, 2]).then(Relu);
+/// kernel.gemm_with_epilogue(1, a, b, 0, &mut c, pack_sizes, &mut packing_buf, epilogue);
+/// assert_eq!(c.as_slice(), [4, 0]);
+/// ```
+///
+/// [`Kernel::gemm_with_epilogue`]: crate::Kernel::gemm_with_epilogue
+pub trait Epilogue<T> {
+    /// Returns the new value of `c[row, col]`, that is `value` before the epilogue.
+    fn apply(&mut self, row: usize, col: usize, value: T) -> T;
+
+    /// Applies `self` and then `next`.
+    fn then<E>(self, next: E) -> Then<Self, E>
+    where
+        Self: Sized,
+        E: Epilogue<T>,
+    {
+        Then(self, next)
+    }
+}
+
+/// Composition of two epilogues, see [`Epilogue::then`].
+#[derive(Debug, Clone, Copy)]
+pub struct Then<A, B>(pub A, pub B);
+
+impl<T, A, B> Epilogue<T> for Then<A, B>
+where
+    A: Epilogue<T>,
+    B: Epilogue<T>,
+{
+    #[inline]
+    fn apply(&mut self, row: usize, col: usize, value: T) -> T {
+        let value = self.0.apply(row, col, value);
+        self.1.apply(row, col, value)
+    }
+}
+
+/// Adds `bias[row]` to every element of a row.
+///
+/// # Panics
+/// If `bias` is shorter than the number of rows of `c`.
+#[derive(Debug, Clone, Copy)]
+pub struct RowBias<'a, T>(pub &'a [T]);
+
+impl<T> Epilogue<T> for RowBias<'_, T>
+where
+    T: Copy + Add<Output = T>,
+{
+    #[inline]
+    fn apply(&mut self, row: usize, _: usize, value: T) -> T {
+        value + self.0[row]
+    }
+}
+
+/// Adds `bias[col]` to every element of a column.
+///
+/// # Panics
+/// If `bias` is shorter than the number of columns of `c`.
+#[derive(Debug, Clone, Copy)]
+pub struct ColBias<'a, T>(pub &'a [T]);
+
+impl<T> Epilogue<T> for ColBias<'_, T>
+where
+    T: Copy + Add<Output = T>,
+{
+    #[inline]
+    fn apply(&mut self, _: usize, col: usize, value: T) -> T {
+        value + self.0[col]
+    }
+}
+
+/// `max(value, 0)`, `NaN` is kept as is.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Relu;
+
+impl<T> Epilogue<T> for Relu
+where
+    T: PartialOrd + Zero,
+{
+    #[inline]
+    fn apply(&mut self, _: usize, _: usize, value: T) -> T {
+        if value < T::zero() {
+            T::zero()
+        } else {
+            value
+        }
+    }
+}
+
+/// Clamps values to `min..=max`, `NaN` is kept as is.
+#[derive(Debug, Clone, Copy)]
+pub struct Clamp<T> {
+    pub min: T,
+    pub max: T,
+}
+
+impl<T> Epilogue<T> for Clamp<T>
+where
+    T: Copy + PartialOrd,
+{
+    #[inline]
+    fn apply(&mut self, _: usize, _: usize, value: T) -> T {
+        if value < self.min {
+            self.min
+        } else if value > self.max {
+            self.max
+        } else {
+            value
+        }
+    }
+}
+
+/// GELU with the `tanh` approximation:
+/// `0.5 x (1 + tanh(sqrt(2 / pi) (x + 0.044715 x^3)))`.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Gelu;
+
+#[cfg(feature = "std")]
+macro_rules! impl_gelu {
+    ($($t:ident),*) => {$(
+        impl Epilogue<$t> for Gelu {
+            #[inline]
+            fn apply(&mut self, _: usize, _: usize, x: $t) -> $t {
+                let sqrt_2_over_pi = (2.0 / core::$t::consts::PI).sqrt();
+                let inner = sqrt_2_over_pi * (x + 0.044715 * x * x * x);
+                0.5 * x * (1.0 + inner.tanh())
+            }
+        }
+    )*};
+}
+
+#[cfg(feature = "std")]
+impl_gelu!(f32, f64);
+
+impl<T, F> Epilogue<T> for F
+where
+    F: FnMut(usize, usize, T) -> T,
+{
+    #[inline]
+    fn apply(&mut self, row: usize, col: usize, value: T) -> T {
+        self(row, col, value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_epilogues() {
+        let bias = [1, 2];
+        assert_eq!(RowBias(&bias).apply(1, 0, 3), 5);
+        assert_eq!(ColBias(&bias).apply(1, 0, 3), 4);
+        assert_eq!(Relu.apply(0, 0, -3), 0);
+        assert_eq!(Relu.apply(0, 0, 3), 3);
+        assert!(Epilogue::<f32>::apply(&mut Relu, 0, 0, f32::NAN).is_nan());
+
+        let mut clamp = Clamp { min: -1, max: 2 };
+        assert_eq!([-5, 0, 5].map(|x| clamp.apply(0, 0, x)), [-1, 0, 2]);
+
+        let mut epilogue = ColBias(&bias).then(Relu).then(Clamp { min: 0, max: 3 });
+        assert_eq!(epilogue.apply(0, 1, 5), 3);
+        assert_eq!(epilogue.apply(0, 0, -5), 0);
+
+        let mut masked = (|row, col, x| if row == col { 0 } else { x }).then(Relu);
+        assert_eq!(masked.apply(1, 1, 5), 0);
+        assert_eq!(masked.apply(0, 1, 5), 5);
+        assert_eq!(masked.apply(0, 1, -5), 0);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_gelu() {
+        let gelu = |x: f64| Gelu.apply(0, 0, x);
+        assert_eq!(gelu(0.0), 0.0);
+        assert_relative_eq!(gelu(1.0), 0.841192, epsilon = 1e-6);
+        assert_relative_eq!(gelu(-1.0), -0.158808, epsilon = 1e-6);
+        assert_relative_eq!(gelu(10.0), 10.0);
+        assert_relative_eq!(gelu(-10.0), 0.0);
+    }
+}
+
+#[cfg(not(miri))]
+#[cfg(test)]
+mod proptests {
+    use super::*;
+    use crate::{
+        as_mut,
+        kernels::{GenericKernel2x2, GenericKernel4x4},
+        std_prelude::*,
+        utils::{arb_matrix_triple_with, naive_gemm},
+        Kernel, MatMut, MatRef, PackSizes,
+    };
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn proptest_gemm_with_epilogue(
+            [a, b, c] in arb_matrix_triple_with(1..20, 1..20, 1..20, -10i32..10),
+            alpha in -10i32..10,
+            beta in -10i32..10,
+            [mc, kc, nc] in [4..20usize, 1..20, 4..20],
+        ) {
+            let [m, n] = [a.nrows(), b.ncols()];
+            let row_bias = (0..m as i32).collect::<Vec<_>>();
+            let col_bias = (0..n as i32).map(|x| -3 * x).collect::<Vec<_>>();
+            let epilogue = || {
+                RowBias(&row_bias).then(ColBias(&col_bias)).then(Clamp { min: -50, max: 200 })
+            };
+
+            let mut expect = c.clone();
+            naive_gemm(alpha, a.to_ref(), b.to_ref(), beta, as_mut!(expect));
+            let mut post = epilogue();
+            for i in 0..m {
+                for j in 0..n {
+                    let z = expect.get_mut(i, j);
+                    *z = post.apply(i, j, *z);
+                }
+            }
+
+            let pack_sizes = PackSizes { mc, kc, nc };
+            let mut packing_buf = vec![0; pack_sizes.buf_len()];
+            let mut actual = c.clone();
+            GenericKernel4x4::new().gemm_with_epilogue(
+                alpha, a.to_ref(), b.to_ref(), beta, as_mut!(actual), pack_sizes, &mut packing_buf, epilogue(),
+            );
+            prop_assert_eq!(expect.as_slice(), actual.as_slice());
+
+            let mut actual = c;
+            GenericKernel2x2::new().gemm_with_epilogue(
+                alpha, a.to_ref(), b.to_ref(), beta, as_mut!(actual), pack_sizes, &mut packing_buf, epilogue(),
+            );
+            prop_assert_eq!(expect.as_slice(), actual.as_slice());
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn proptest_gemm_with_epilogue_closure(
+            [a, b, c] in arb_matrix_triple_with(1..20, 1..20, 1..20, -10i32..10),
+            alpha in -10i32..10,
+            beta in -10i32..10,
+            [mc, kc, nc] in [4..20usize, 1..20, 4..20],
+        ) {
+            let [m, n] = [a.nrows(), b.ncols()];
+            let scales = (0..m as i32).map(|x| x % 3 - 1).collect::<Vec<_>>();
+            // masks the upper triangle and scales the rows
+            let post = |row: usize, col: usize, x: i32| if col > row { -1 } else { scales[row] * x };
+
+            let mut expect = c.clone();
+            naive_gemm(alpha, a.to_ref(), b.to_ref(), beta, as_mut!(expect));
+            for i in 0..m {
+                for j in 0..n {
+                    let z = expect.get_mut(i, j);
+                    *z = post(i, j, *z);
+                }
+            }
+
+            let pack_sizes = PackSizes { mc, kc, nc };
+            let mut packing_buf = vec![0; pack_sizes.buf_len()];
+            let mut actual = c;
+            let mut calls = vec![0; m * n];
+            let epilogue = |row, col, x| {
+                calls[row * n + col] += 1;
+                post(row, col, x)
+            };
+            GenericKernel4x4::new().gemm_with_epilogue(
+                alpha, a.to_ref(), b.to_ref(), beta, as_mut!(actual), pack_sizes, &mut packing_buf, epilogue,
+            );
+            prop_assert_eq!(expect.as_slice(), actual.as_slice());
+            prop_assert!(calls.iter().all(|&x| x == 1));
+        }
+    }
+
+    #[test]
+    fn test_gemm_with_epilogue_empty_k() {
+        let kernel = GenericKernel4x4::new();
+        let pack_sizes = PackSizes {
+            mc: 4,
+            kc: 4,
+            nc: 4,
+        };
+        let mut packing_buf = vec![0; pack_sizes.buf_len()];
+        let a = MatRef::row_major(2, 0, &[]);
+        let b = MatRef::row_major(0, 2, &[]);
+        let mut c = [1, -2, 3, -4];
+        let mut c = MatMut::row_major(2, 2, &mut c);
+
+        let epilogue = RowBias(&[10, 20]).then(Relu);
+        kernel.gemm_with_epilogue(1, a, b, 2, &mut c, pack_sizes, &mut packing_buf, epilogue);
+        assert_eq!(c.as_slice(), [12, 6, 26, 12]);
+    }
+}
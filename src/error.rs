@@ -0,0 +1,67 @@
+use crate::PackSizes;
+use core::fmt;
+
+/// Error returned by the fallible gemm functions, such as [`Kernel::try_gemm`].
+///
+/// [`Kernel::try_gemm`]: crate::Kernel::try_gemm
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum GemmError {
+    /// Dimensions of `a`, `b` and `c` are not compatible,
+    /// each field is `[nrows, ncols]` of the corresponding matrix.
+    DimMismatch {
+        a: [usize; 2],
+        b: [usize; 2],
+        c: [usize; 2],
+    },
+    /// Length of the provided buffer is not equal to the expected one.
+    BufferLen { expected: usize, got: usize },
+    /// `mc` is less than `mr`, `nc` is less than `nr` or `kc` is zero.
+    PackSizesTooSmall {
+        pack_sizes: PackSizes,
+        mr: usize,
+        nr: usize,
+    },
+    /// Required buffer length overflows `usize`.
+    Overflow,
+    /// The operand was packed for a kernel with a different tile size
+    /// (`mr` for [`PackedA`](crate::PackedA), `nr` for [`PackedB`](crate::PackedB)) than the one used by gemm.
+    PackedTileMismatch { packed: usize, kernel: usize },
+    /// The batches of `a`, `b` and `c` have different lengths.
+    BatchLen { a: usize, b: usize, c: usize },
+    /// A matrix of a strided batch is out of its values
+    /// or the matrices of `c` overlap.
+    BatchStride,
+}
+
+impl fmt::Display for GemmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DimMismatch { a, b, c } => write!(
+                f,
+                "dimension mismatch: a is {}x{}, b is {}x{}, c is {}x{}",
+                a[0], a[1], b[0], b[1], c[0], c[1]
+            ),
+            Self::BufferLen { expected, got } => {
+                write!(f, "invalid buffer length: expected {expected}, got {got}")
+            }
+            Self::PackSizesTooSmall { pack_sizes, mr, nr } => write!(
+                f,
+                "pack sizes are too small: {pack_sizes:?} for mr = {mr}, nr = {nr}"
+            ),
+            Self::Overflow => write!(f, "buffer length overflows usize"),
+            Self::PackedTileMismatch { packed, kernel } => write!(
+                f,
+                "operand was packed for a tile size of {packed}, but the kernel uses {kernel}"
+            ),
+            Self::BatchLen { a, b, c } => write!(
+                f,
+                "batch length mismatch: a has {a} matrices, b has {b}, c has {c}"
+            ),
+            Self::BatchStride => write!(f, "batch is out of bounds or the batches of c overlap"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for GemmError {}
@@ -0,0 +1,218 @@
+use crate::gemm::check_gemm_args;
+use crate::par_loops::par_gemm_loops;
+use crate::{GemmError, Kernel, MatMut, MatRef, PackSizes};
+use num_traits::{One, Zero};
+
+/// Runs the tasks of a parallel gemm, see [`Kernel::gemm_with_executor`].
+///
+/// The gemm is computed by [`workers`](Self::workers) workers, which share each packed panel
+/// of `b` and compute the blocks of `c` in parallel. The executor is only asked to run a task
+/// on each worker, so that it can be backed by any scheduler, including `no_std` ones.
+///
+/// # Examples
+///
+/// ```
+/// use microgemm::Executor;
+///
+/// // Runs the task of each worker one after another on the current thread.
+/// struct Sequential(usize);
+///
+/// impl Executor for Sequential {
+///     fn workers(&self) -> usize {
+///         self.0
+///     }
+///
+///     fn run(&self, task: &(dyn Fn() + Sync)) {
+///         (0..self.0).for_each(|_| task());
+///     }
+/// }
+/// ```
+pub trait Executor {
+    /// Number of workers, which sets the length of the workspace,
+    /// see [`PackSizes::par_buf_len`].
+    fn workers(&self) -> usize;
+
+    /// Calls `task` once on each worker, possibly in parallel,
+    /// and returns when all of the calls are finished.
+    ///
+    /// Each call computes blocks of `c` until none are left, so the result does not depend
+    /// on the number of calls, only the parallelism does.
+    fn run(&self, task: &(dyn Fn() + Sync));
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn try_gemm_with_executor<T, K, E>(
+    kernel: &K,
+    executor: &E,
+    alpha: T,
+    a: MatRef<T>,
+    b: MatRef<T>,
+    beta: T,
+    c: &mut MatMut<T>,
+    pack_sizes: PackSizes,
+    workspace: &mut [T],
+) -> Result<(), GemmError>
+where
+    T: Copy + Zero + One + Send + Sync,
+    K: Kernel<Scalar = T> + Sync + ?Sized,
+    E: Executor + ?Sized,
+{
+    let workers = executor.workers();
+    assert!(workers > 0, "executor must have at least one worker");
+
+    let [mr, nr] = [K::MR, K::NR];
+    let buf_len = pack_sizes.checked_buf_len().unwrap_or_default();
+    let clamped = check_gemm_args(mr, nr, a, b, c.to_ref(), pack_sizes, buf_len)?;
+    let expected = pack_sizes
+        .checked_par_buf_len(workers)
+        .ok_or(GemmError::Overflow)?;
+    if workspace.len() != expected {
+        return Err(GemmError::BufferLen {
+            expected,
+            got: workspace.len(),
+        });
+    }
+
+    let (bpack, apacks) = pack_sizes.split_par_buf(workers, workspace);
+    let PackSizes { mc, kc, nc } = clamped;
+    let bpack = &mut bpack[..kc * nc];
+    let apacks = &mut apacks[..workers * mc * kc];
+    let run = |task: &(dyn Fn() + Sync)| executor.run(task);
+    par_gemm_loops(kernel, alpha, a, b, beta, c, clamped, bpack, apacks, &run);
+    Ok(())
+}
+
+#[cfg(not(miri))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        as_mut,
+        kernels::GenericKernel4x4,
+        std_prelude::*,
+        utils::{arb_matrix_triple_with, naive_gemm},
+    };
+    use proptest::prelude::*;
+
+    struct Threads(usize);
+
+    impl Executor for Threads {
+        fn workers(&self) -> usize {
+            self.0
+        }
+
+        fn run(&self, task: &(dyn Fn() + Sync)) {
+            std::thread::scope(|s| {
+                for _ in 0..self.0 {
+                    s.spawn(task);
+                }
+            });
+        }
+    }
+
+    fn gemm_with_threads(
+        threads: usize,
+        alpha: i32,
+        a: MatRef<i32>,
+        b: MatRef<i32>,
+        beta: i32,
+        c: &mut MatMut<i32>,
+        pack_sizes: PackSizes,
+    ) {
+        let kernel = GenericKernel4x4::new();
+        let executor = Threads(threads);
+        let mut workspace = vec![-1; pack_sizes.par_buf_len(threads)];
+        kernel.gemm_with_executor(&executor, alpha, a, b, beta, c, pack_sizes, &mut workspace);
+    }
+
+    proptest! {
+        #[test]
+        fn proptest_gemm_with_executor(
+            [a, b, c] in arb_matrix_triple_with(0..40, 0..40, 0..40, -10i32..10),
+            alpha in -10i32..10,
+            beta in -10i32..10,
+            [mc, kc, nc] in [1..4usize, 1..20, 1..4],
+        ) {
+            let pack_sizes = PackSizes { mc: 4 * mc, kc, nc: 4 * nc };
+            let mut expect = c.clone();
+            naive_gemm(alpha, a.to_ref(), b.to_ref(), beta, as_mut!(expect));
+
+            let mut actual = c.clone();
+            gemm_with_threads(1, alpha, a.to_ref(), b.to_ref(), beta, as_mut!(actual), pack_sizes);
+            prop_assert_eq!(expect.as_slice(), actual.as_slice());
+
+            let mut actual = c.clone();
+            gemm_with_threads(3, alpha, a.to_ref(), b.to_ref(), beta, as_mut!(actual), pack_sizes);
+            prop_assert_eq!(expect.as_slice(), actual.as_slice());
+
+            let mut actual = c;
+            gemm_with_threads(8, alpha, a.to_ref(), b.to_ref(), beta, as_mut!(actual), pack_sizes);
+            prop_assert_eq!(expect.as_slice(), actual.as_slice());
+        }
+    }
+
+    #[test]
+    fn test_gemm_with_executor_workspace_len() {
+        let kernel = GenericKernel4x4::<i32>::new();
+        let executor = Threads(3);
+        let pack_sizes = PackSizes {
+            mc: 4,
+            kc: 2,
+            nc: 4,
+        };
+        let a = MatRef::row_major(1, 1, &[1]);
+        let mut c = [0];
+        let mut c = MatMut::row_major(1, 1, &mut c);
+
+        let mut workspace = vec![0; pack_sizes.buf_len()];
+        let result = try_gemm_with_executor(
+            &kernel,
+            &executor,
+            1,
+            a,
+            a,
+            0,
+            &mut c,
+            pack_sizes,
+            &mut workspace,
+        );
+        let expect = GemmError::BufferLen {
+            expected: pack_sizes.par_buf_len(3),
+            got: pack_sizes.buf_len(),
+        };
+        assert_eq!(result, Err(expect));
+    }
+
+    #[test]
+    fn test_gemm_with_executor_skipping_tasks() {
+        // runs no task, the blocks left are computed on the current thread
+        struct Idle;
+
+        impl Executor for Idle {
+            fn workers(&self) -> usize {
+                4
+            }
+
+            fn run(&self, _: &(dyn Fn() + Sync)) {}
+        }
+
+        let kernel = GenericKernel4x4::<i32>::new();
+        let pack_sizes = PackSizes {
+            mc: 4,
+            kc: 2,
+            nc: 4,
+        };
+        let a = (0..9 * 5).collect::<Vec<_>>();
+        let b = (0..5 * 6).map(|x| x % 7 - 3).collect::<Vec<_>>();
+        let a = MatRef::row_major(9, 5, &a);
+        let b = MatRef::row_major(5, 6, &b);
+        let mut expect = vec![1; 9 * 6];
+        naive_gemm(2, a, b, 3, &mut MatMut::row_major(9, 6, &mut expect));
+
+        let mut actual = vec![1; 9 * 6];
+        let mut c = MatMut::row_major(9, 6, &mut actual);
+        let mut workspace = vec![0; pack_sizes.par_buf_len(Idle.workers())];
+        kernel.gemm_with_executor(&Idle, 2, a, b, 3, &mut c, pack_sizes, &mut workspace);
+        assert_eq!(actual, expect);
+    }
+}
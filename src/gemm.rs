@@ -1,5 +1,8 @@
 use crate::kernel::Multiply;
-use crate::{Kernel, MatMut, MatRef, PackSizes};
+use crate::packing::{block_len, BlocksA, Packer, PanelsB};
+use crate::small::{gemm_small, is_small};
+use crate::{DynKernel, Epilogue, GemmError, Kernel, MatMut, MatRef, PackSizes};
+use core::ops::Range;
 use generic_array::{sequence::GenericSequence, GenericArray};
 use num_traits::{One, Zero};
 
@@ -20,94 +23,501 @@ pub(crate) fn gemm_with_kernel<T, K>(
     T: Copy + Zero + One,
     K: Kernel<Scalar = T> + ?Sized,
 {
-    assert_eq!(a.nrows(), c.nrows());
-    assert_eq!(a.ncols(), b.nrows());
-    assert_eq!(b.ncols(), c.ncols());
-    let [m, k, n] = [a.nrows(), a.ncols(), c.ncols()];
-
-    assert_eq!(
-        packing_buf.len(),
-        pack_sizes
-            .checked_buf_len()
-            .expect("PackSizes::buf_len should not overflow")
+    let epilogue = None::<&mut dyn Epilogue<T>>;
+    let result = try_gemm_with_kernel(
+        kernel,
+        alpha,
+        a,
+        b,
+        beta,
+        c,
+        pack_sizes,
+        packing_buf,
+        epilogue,
     );
-    let pack_sizes = pack_sizes.clamped(kernel);
-    let packing_buf = packing_buf[..pack_sizes.checked_buf_len().unwrap()].as_mut();
-    let (apack, bpack) = pack_sizes.split_buf(packing_buf);
+    if let Err(err) = result {
+        panic!("{err}");
+    }
+}
 
-    let mr = K::MR;
-    let nr = K::NR;
+#[allow(clippy::too_many_arguments)]
+#[inline]
+pub(crate) fn try_gemm_with_kernel<T, K, E>(
+    kernel: &K,
+    alpha: T,
+    a: MatRef<T>,
+    b: MatRef<T>,
+    beta: T,
+    c: &mut MatMut<T>,
+    pack_sizes: PackSizes,
+    packing_buf: &mut [T],
+    epilogue: Option<&mut E>,
+) -> Result<(), GemmError>
+where
+    T: Copy + Zero + One,
+    K: Kernel<Scalar = T> + ?Sized,
+    E: Epilogue<T> + ?Sized,
+{
+    let zero = Zero::zero();
+    let mut dst_buf = GenericArray::<T, Product<K::Mr, K::Nr>>::generate(|_| zero);
+    // small problems skip the packing buffer, see `gemm_small`
+    let [mr, nr] = [K::MR, K::NR];
+    if is_small(mr, nr, [c.nrows(), a.ncols(), c.ncols()]) {
+        check_gemm_args(mr, nr, a, b, c.to_ref(), pack_sizes, packing_buf.len())?;
+        let dst_buf = dst_buf.as_mut_slice();
+        gemm_small(kernel, alpha, a, b, beta, c, dst_buf, epilogue);
+        return Ok(());
+    }
+    try_gemm_with_dyn_kernel(
+        kernel,
+        alpha,
+        a,
+        b,
+        beta,
+        c,
+        pack_sizes,
+        packing_buf,
+        dst_buf.as_mut_slice(),
+        epilogue,
+    )
+}
+
+/// Computes `c <- alpha a b + beta c` with a kernel chosen at runtime.
+///
+/// Unlike [`Kernel::gemm`], the `mr x nr` tile of the microkernel is stored in the
+/// caller-provided `dst_buf`, so the tile dimensions do not have to be known
+/// at compile time.
+///
+/// # Panics
+/// 1. On the same conditions as [`Kernel::gemm`].
+/// 2. If `dst_buf.len() < kernel.dyn_mr() * kernel.dyn_nr()`.
+///
+/// [`Kernel::gemm`]: crate::Kernel::gemm
+///
+/// # Examples
+///
+/// ```
+/// use microgemm::{
+///     gemm_dyn,
+///     kernels::{GenericKernel2x2, GenericKernel4x4},
+///     DynKernel, MatMut, MatRef, PackSizes,
+/// };
+///
+/// let kernels: [&dyn DynKernel<i32>; 2] = [&GenericKernel2x2::new(), &GenericKernel4x4::new()];
+///
+/// let a = MatRef::row_major(2, 3, &[1, 2, 3, 4, 5, 6]);
+/// let b = MatRef::row_major(3, 2, &[10, 11, 20, 21, 30, 31]);
+///
+/// let pack_sizes = PackSizes { mc: 4, kc: 3, nc: 4 };
+/// let mut packing_buf = [0; PackSizes { mc: 4, kc: 3, nc: 4 }.buf_len()];
+/// let mut dst_buf = [0; 4 * 4];
+///
+/// for kernel in kernels {
+///     let mut c = [0; 2 * 2];
+///     let mut c = MatMut::row_major(2, 2, &mut c);
+///     gemm_dyn(kernel, 1, a, b, 0, &mut c, pack_sizes, &mut packing_buf, &mut dst_buf);
+///     assert_eq!(c.as_slice(), [140, 146, 320, 335]);
+/// }
+/// ```
+#[allow(clippy::too_many_arguments)]
+pub fn gemm_dyn<T>(
+    kernel: &dyn DynKernel<T>,
+    alpha: T,
+    a: MatRef<T>,
+    b: MatRef<T>,
+    beta: T,
+    c: &mut MatMut<T>,
+    pack_sizes: PackSizes,
+    packing_buf: &mut [T],
+    dst_buf: &mut [T],
+) where
+    T: Copy + Zero + One,
+{
+    let result = try_gemm_with_dyn_kernel(
+        kernel,
+        alpha,
+        a,
+        b,
+        beta,
+        c,
+        pack_sizes,
+        packing_buf,
+        dst_buf,
+        None::<&mut dyn Epilogue<T>>,
+    );
+    if let Err(err) = result {
+        panic!("{err}");
+    }
+}
+
+/// Checks the arguments of gemm and returns the `PackSizes` clamped to `mr` and `nr`.
+pub(crate) fn check_gemm_args<A, B, C>(
+    mr: usize,
+    nr: usize,
+    a: MatRef<A>,
+    b: MatRef<B>,
+    c: MatRef<C>,
+    pack_sizes: PackSizes,
+    packing_buf_len: usize,
+) -> Result<PackSizes, GemmError> {
+    check_dims(a, b, c)?;
+    check_pack_sizes(mr, nr, pack_sizes, packing_buf_len)
+}
+
+/// Checks `pack_sizes` against the kernel and the length of the packing buffer,
+/// and returns them clamped to `mr` and `nr`.
+pub(crate) fn check_pack_sizes(
+    mr: usize,
+    nr: usize,
+    pack_sizes: PackSizes,
+    packing_buf_len: usize,
+) -> Result<PackSizes, GemmError> {
+    if pack_sizes.mc < mr || pack_sizes.nc < nr || pack_sizes.kc == 0 {
+        return Err(GemmError::PackSizesTooSmall { pack_sizes, mr, nr });
+    }
+    let expected = pack_sizes.checked_buf_len().ok_or(GemmError::Overflow)?;
+    if packing_buf_len != expected {
+        return Err(GemmError::BufferLen {
+            expected,
+            got: packing_buf_len,
+        });
+    }
+    Ok(pack_sizes.clamped(mr, nr))
+}
+
+pub(crate) fn check_dims<A, B, C>(
+    a: MatRef<A>,
+    b: MatRef<B>,
+    c: MatRef<C>,
+) -> Result<(), GemmError> {
+    if a.nrows() != c.nrows() || a.ncols() != b.nrows() || b.ncols() != c.ncols() {
+        return Err(GemmError::DimMismatch {
+            a: [a.nrows(), a.ncols()],
+            b: [b.nrows(), b.ncols()],
+            c: [c.nrows(), c.ncols()],
+        });
+    }
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+#[inline]
+fn try_gemm_with_dyn_kernel<T, K, E>(
+    kernel: &K,
+    alpha: T,
+    a: MatRef<T>,
+    b: MatRef<T>,
+    beta: T,
+    c: &mut MatMut<T>,
+    pack_sizes: PackSizes,
+    packing_buf: &mut [T],
+    dst_buf: &mut [T],
+    epilogue: Option<&mut E>,
+) -> Result<(), GemmError>
+where
+    T: Copy + Zero + One,
+    K: DynKernel<T> + ?Sized,
+    E: Epilogue<T> + ?Sized,
+{
+    let mr = kernel.dyn_mr();
+    let nr = kernel.dyn_nr();
     assert!(mr > 0);
     assert!(nr > 0);
 
-    let [mc, nc] = [pack_sizes.mc, pack_sizes.nc];
+    let pack_sizes = check_gemm_args(mr, nr, a, b, c.to_ref(), pack_sizes, packing_buf.len())?;
+    let tile_len = mr.checked_mul(nr).ok_or(GemmError::Overflow)?;
+    if dst_buf.len() < tile_len {
+        return Err(GemmError::BufferLen {
+            expected: tile_len,
+            got: dst_buf.len(),
+        });
+    }
+    let dst_buf = &mut dst_buf[..tile_len];
+
+    let packing_buf = packing_buf[..pack_sizes.checked_buf_len().unwrap()].as_mut();
+    gemm_packing(
+        kernel,
+        alpha,
+        a,
+        b,
+        beta,
+        c,
+        pack_sizes,
+        packing_buf,
+        dst_buf,
+        epilogue,
+    );
+    Ok(())
+}
+
+// c <- alpha a b + beta c for checked arguments and clamped `pack_sizes`,
+// packing `a` and `b` into `packing_buf` of `pack_sizes.buf_len()` values.
+#[allow(clippy::too_many_arguments)]
+#[inline]
+pub(crate) fn gemm_packing<T, K, E>(
+    kernel: &K,
+    alpha: T,
+    a: MatRef<T>,
+    b: MatRef<T>,
+    beta: T,
+    c: &mut MatMut<T>,
+    pack_sizes: PackSizes,
+    packing_buf: &mut [T],
+    dst_buf: &mut [T],
+    epilogue: Option<&mut E>,
+) where
+    T: Copy + Zero + One,
+    K: DynKernel<T> + ?Sized,
+    E: Epilogue<T> + ?Sized,
+{
+    let (apack, bpack) = pack_sizes.split_buf(packing_buf);
+    let mut a_blocks = Packer::new(a, apack);
+    let mut b_panels = Packer::new(b, bpack);
+    let k = a.ncols();
+    gemm_loops(
+        kernel,
+        alpha,
+        &mut a_blocks,
+        &mut b_panels,
+        beta,
+        c,
+        k,
+        pack_sizes,
+        dst_buf,
+        epilogue,
+    );
+}
+
+// The jc, pc and ic loops of gemm for checked arguments and clamped `pack_sizes`,
+// shared by all the drivers, which only differ in where the packed blocks of `a`
+// and panels of `b` come from. The blocks are trimmed to `c` rounded up to whole tiles,
+// so a small `c` takes a single block. The epilogue is applied to the tiles of the
+// last pc block, or to `beta c` if `k` is zero.
+#[allow(clippy::too_many_arguments)]
+#[inline]
+pub(crate) fn gemm_loops<T, K, E, A, B>(
+    kernel: &K,
+    alpha: T,
+    a: &mut A,
+    b: &mut B,
+    beta: T,
+    c: &mut MatMut<T>,
+    k: usize,
+    pack_sizes: PackSizes,
+    dst_buf: &mut [T],
+    mut epilogue: Option<&mut E>,
+) where
+    T: Copy + Zero + One,
+    K: DynKernel<T> + ?Sized,
+    E: Epilogue<T> + ?Sized,
+    A: BlocksA<T> + ?Sized,
+    B: PanelsB<T> + ?Sized,
+{
+    let mr = kernel.dyn_mr();
+    let nr = kernel.dyn_nr();
+    let [m, n] = [c.nrows(), c.ncols()];
+    let PackSizes { mc, kc, nc } = pack_sizes;
     assert!(mr <= mc);
     assert_eq!(mc % mr, 0);
     assert!(nr <= nc);
     assert_eq!(nc % nr, 0);
 
-    let zero = Zero::zero();
-    let mut dst_buf = GenericArray::<T, Product<K::Mr, K::Nr>>::generate(|_| zero);
-    let dst_buf = dst_buf.as_mut_slice();
+    if m == 0 || n == 0 {
+        return;
+    }
+    if k == 0 {
+        // alpha a b is an empty sum, as in reference BLAS
+        scale(beta, c);
+        if let Some(epilogue) = epilogue {
+            apply_epilogue(epilogue, c, [0, 0], [m, n]);
+        }
+        return;
+    }
 
     for jc in (0..n).step_by(nc) {
-        for (l4, pc) in (0..k).step_by(pack_sizes.kc).enumerate() {
+        let nc = block_len(nc, nr, n - jc);
+        for (l4, pc) in (0..k).step_by(kc).enumerate() {
             let beta = if l4 == 0 { beta } else { One::one() };
 
-            let kc = (pc + pack_sizes.kc).min(k) - pc;
+            let kc = (pc + kc).min(k) - pc;
             debug_assert!(pc + kc <= k);
-
-            let bpack = {
-                let rows = pc..pc + kc;
-                let cols = jc..jc + nc;
-                let bpack = &mut bpack[..kc * nc];
-                crate::packing::pack_b(nr, bpack, b, rows, cols);
-                bpack
+            let mut epilogue = if pc + kc == k {
+                epilogue.as_deref_mut()
+            } else {
+                None
             };
 
+            let bpack = b.panel_b(nr, pc..pc + kc, jc..jc + nc);
+            debug_assert_eq!(bpack.len(), kc * nc);
+
             for ic in (0..m).step_by(mc) {
-                let apack = {
-                    let rows = ic..ic + mc;
-                    let cols = pc..pc + kc;
-                    let apack = &mut apack[..mc * kc];
-                    crate::packing::pack_a(mr, apack, a, rows, cols);
-                    apack
-                };
-
-                for (l2, jr) in (0..nc).step_by(nr).enumerate() {
-                    let rsize = kc * nr;
-                    let rhs_values = &bpack[rsize * l2..rsize * (l2 + 1)];
-                    let rhs = MatRef::row_major(kc, nr, rhs_values);
-
-                    let dst_cols = jc + jr..jc + jr + nr;
-
-                    for (l1, ir) in (0..mc).step_by(mr).enumerate() {
-                        let lsize = mr * kc;
-                        let lhs_values = &apack[lsize * l1..lsize * (l1 + 1)];
-                        let lhs = MatRef::col_major(mr, kc, lhs_values);
-
-                        let dst_rows = ic + ir..ic + ir + mr;
-                        crate::packing::registers_from_c(
-                            dst_buf,
-                            c.to_ref(),
-                            dst_rows.clone(),
-                            dst_cols.clone(),
-                        );
-                        let mut dst = MatMut::col_major(mr, nr, dst_buf);
-                        kernel.microkernel(alpha, lhs, rhs, beta, &mut dst);
-                        crate::packing::registers_to_c(dst_buf, c, dst_rows, dst_cols.clone());
-                    }
-                }
+                let mc = block_len(mc, mr, m - ic);
+                let apack = a.block_a(mr, ic..ic + mc, pc..pc + kc);
+                debug_assert_eq!(apack.len(), mc * kc);
+
+                let (rows, cols) = (ic..ic + mc, jc..jc + nc);
+                let epilogue = epilogue.as_deref_mut();
+                packed_macro_kernel(
+                    kernel, alpha, apack, bpack, beta, c, rows, cols, dst_buf, epilogue,
+                );
             }
         }
     }
 }
 
+// Computes c[rows, cols] <- alpha apack bpack + beta c[rows, cols],
+// where `apack` and `bpack` are packed by `pack_a` and `pack_b`.
+// Rows and columns outside of `c` are ignored.
+#[allow(clippy::too_many_arguments)]
+#[inline]
+pub(crate) fn packed_macro_kernel<T, K, E>(
+    kernel: &K,
+    alpha: T,
+    apack: &[T],
+    bpack: &[T],
+    beta: T,
+    c: &mut MatMut<T>,
+    rows: Range<usize>,
+    cols: Range<usize>,
+    dst_buf: &mut [T],
+    mut epilogue: Option<&mut E>,
+) where
+    T: Copy + Zero + One,
+    K: DynKernel<T> + ?Sized,
+    E: Epilogue<T> + ?Sized,
+{
+    let mr = kernel.dyn_mr();
+    let nr = kernel.dyn_nr();
+    let [mc, nc] = [rows.len(), cols.len()];
+    let kc = apack.len() / mc;
+    debug_assert_eq!(apack.len(), mc * kc);
+    debug_assert_eq!(bpack.len(), kc * nc);
+    debug_assert_eq!(dst_buf.len(), mr * nr);
+
+    let [ic, jc] = [rows.start, cols.start];
+
+    for (l2, jr) in (0..nc).step_by(nr).enumerate() {
+        let rsize = kc * nr;
+        let rhs = &bpack[rsize * l2..rsize * (l2 + 1)];
+
+        let dst_cols = jc + jr..jc + jr + nr;
+
+        for (l1, ir) in (0..mc).step_by(mr).enumerate() {
+            let lsize = mr * kc;
+            let lhs = &apack[lsize * l1..lsize * (l1 + 1)];
+
+            let dst_rows = ic + ir..ic + ir + mr;
+            tile_kernel(
+                kernel,
+                alpha,
+                lhs,
+                rhs,
+                beta,
+                c,
+                dst_rows,
+                dst_cols.clone(),
+                dst_buf,
+                epilogue.as_deref_mut(),
+            );
+        }
+    }
+}
+
+// Computes the tile c[rows, cols] <- alpha lhs rhs + beta c[rows, cols]
+// for `mr x kc` and `kc x nr` panels `lhs` and `rhs`. Rows and columns outside of `c` are ignored.
+// A full tile of a `c` with unit row stride is computed in place if the kernel allows it,
+// otherwise the tile goes through `dst_buf`. The epilogue, if any, is applied
+// to the computed tile before it is written back to `c`.
+#[allow(clippy::too_many_arguments)]
+#[inline]
+pub(crate) fn tile_kernel<T, K, E>(
+    kernel: &K,
+    alpha: T,
+    lhs: &[T],
+    rhs: &[T],
+    beta: T,
+    c: &mut MatMut<T>,
+    rows: Range<usize>,
+    cols: Range<usize>,
+    dst_buf: &mut [T],
+    epilogue: Option<&mut E>,
+) where
+    T: Copy + Zero + One,
+    K: DynKernel<T> + ?Sized,
+    E: Epilogue<T> + ?Sized,
+{
+    let in_place = kernel.dyn_strided_dst()
+        && c.row_stride() == 1
+        && c.col_stride() >= rows.len()
+        && rows.end <= c.nrows()
+        && cols.end <= c.ncols();
+    if in_place {
+        let col_stride = c.col_stride();
+        let [mr, nr] = [rows.len(), cols.len()];
+        let at = [rows.start, cols.start];
+        let mut dst = c.submatrix_mut(rows, cols);
+        kernel.dyn_microkernel_strided(alpha, lhs, rhs, beta, dst.as_mut_slice(), col_stride);
+        if let Some(epilogue) = epilogue {
+            apply_epilogue(epilogue, &mut dst, at, [mr, nr]);
+        }
+        return;
+    }
+
+    // c is not read if beta is zero, as in reference BLAS
+    if beta.is_zero() {
+        kernel.dyn_microkernel_overwrite(alpha, lhs, rhs, dst_buf);
+    } else {
+        crate::packing::registers_from_c(dst_buf, c.to_ref(), rows.clone(), cols.clone());
+        kernel.dyn_microkernel(alpha, lhs, rhs, beta, dst_buf);
+    }
+    if let Some(epilogue) = epilogue {
+        let [mr, nr] = [rows.len(), cols.len()];
+        let nrows = c.nrows().min(rows.end).saturating_sub(rows.start);
+        let ncols = c.ncols().min(cols.end).saturating_sub(cols.start);
+        let mut dst = MatMut::col_major(mr, nr, &mut *dst_buf);
+        apply_epilogue(epilogue, &mut dst, [rows.start, cols.start], [nrows, ncols]);
+    }
+    crate::packing::registers_to_c(dst_buf, c, rows, cols);
+}
+
+// Applies the epilogue to dst[..nrows, ..ncols], where `dst` starts at `[row, col]` of c.
+fn apply_epilogue<T, E>(
+    epilogue: &mut E,
+    dst: &mut MatMut<T>,
+    [row, col]: [usize; 2],
+    [nrows, ncols]: [usize; 2],
+) where
+    T: Copy,
+    E: Epilogue<T> + ?Sized,
+{
+    for j in 0..ncols {
+        for i in 0..nrows {
+            let z = dst.get_mut(i, j);
+            *z = epilogue.apply(row + i, col + j, *z);
+        }
+    }
+}
+
+// c <- beta c, c is overwritten with zeros if beta is zero
+pub(crate) fn scale<T>(beta: T, c: &mut MatMut<T>)
+where
+    T: Copy + Zero + One,
+{
+    let overwrite = beta.is_zero();
+    for row in 0..c.nrows() {
+        for col in 0..c.ncols() {
+            let z = c.get_mut(row, col);
+            *z = if overwrite { beta } else { beta * *z };
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::std_prelude::*;
     use crate::{
+        kernels::GenericKernel4x4,
         typenum::{U4, U5},
         utils::naive_gemm,
     };
@@ -262,4 +672,337 @@ mod tests {
         naive_gemm(alpha, a, b, beta, expect.as_mut());
         assert_eq!(expect.as_slice(), c.as_slice());
     }
+
+    #[rustfmt::skip]
+    #[test]
+    fn test_gemm_transposed() {
+        let kernel = TestKernel;
+
+        let a = [
+            28, 26, -9, -29, 7,
+            29, -8, 23, 22, -1,
+            -2, -2, 26, -21, 3,
+            -29, 2, 26, -17, 0,
+            -22, -18, -24, -23, 5,
+            -20, 14, 13, -22, -6,
+        ];
+        let a = MatRef::row_major(6, 5, &a);
+        let b = [
+            2, -24, 20,
+            -27, -1, -16,
+            -12, -29, -26,
+            -16, -13, -18,
+            4, 8, -3,
+        ];
+        let b = MatRef::col_major(5, 3, &b);
+        let mut c = (0..6 * 3).map(|x| x - 9).collect::<Vec<i32>>();
+        let mut expect = c.clone();
+        let mut expect = MatMut::row_major(6, 3, &mut expect);
+
+        let alpha = 4;
+        let beta = -3;
+
+        let pack_sizes = PackSizes { mc: kernel.mr(), kc: 2, nc: kernel.nr() };
+        let mut buf = vec![-2; pack_sizes.buf_len()];
+
+        // c^T <- alpha b^T a^T + beta c^T
+        let mut ct = MatMut::row_major(6, 3, &mut c).t();
+        kernel.gemm(alpha, b.t(), a.t(), beta, &mut ct, pack_sizes, &mut buf);
+        naive_gemm(alpha, a, b, beta, expect.as_mut());
+        assert_eq!(expect.as_slice(), c.as_slice());
+    }
+
+    #[rustfmt::skip]
+    #[test]
+    fn test_gemm_dyn_heterogeneous() {
+        use crate::kernels::{GenericKernel2x2, GenericKernel8x8};
+
+        let kernels: [&dyn DynKernel<i32>; 3] = [
+            &TestKernel,
+            &GenericKernel2x2::new(),
+            &GenericKernel8x8::new(),
+        ];
+
+        let a = [
+            28, 26, -9, -29,
+            29, -8, 23, 22,
+            -2, -2, 26, -21,
+            -29, 2, 26, -17,
+            -22, -18, -24, -23,
+            -20, 14, 13, -22,
+        ];
+        let a = MatRef::row_major(6, 4, &a);
+        let b = [
+            2, -24, 20,
+            -27, -1, -16,
+            -12, -29, -26,
+            -16, -13, -18,
+        ];
+        let b = MatRef::row_major(4, 3, &b);
+        let c = (0..6 * 3).collect::<Vec<_>>();
+
+        let alpha = 4;
+        let beta = -3;
+
+        let mut expect = c.clone();
+        let mut expect = MatMut::row_major(6, 3, &mut expect);
+        naive_gemm(alpha, a, b, beta, expect.as_mut());
+
+        let pack_sizes = PackSizes { mc: 8, kc: 3, nc: 10 };
+        let mut buf = vec![-1; pack_sizes.buf_len()];
+        let mut dst_buf = [-2; 8 * 8];
+
+        for kernel in kernels {
+            let mut c = c.clone();
+            let mut c = MatMut::row_major(6, 3, &mut c);
+            gemm_dyn(kernel, alpha, a, b, beta, &mut c, pack_sizes, &mut buf, &mut dst_buf);
+            assert_eq!(expect.as_slice(), c.as_slice());
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_gemm_dyn_small_dst_buf() {
+        let a = MatRef::row_major(1, 1, &[1]);
+        let b = MatRef::row_major(1, 1, &[1]);
+        let mut c = [0];
+        let mut c = MatMut::row_major(1, 1, &mut c);
+
+        let pack_sizes = PackSizes {
+            mc: 4,
+            kc: 1,
+            nc: 5,
+        };
+        let mut buf = vec![0; pack_sizes.buf_len()];
+        let mut dst_buf = [0; 4 * 5 - 1];
+        gemm_dyn(
+            &TestKernel,
+            1,
+            a,
+            b,
+            0,
+            &mut c,
+            pack_sizes,
+            &mut buf,
+            &mut dst_buf,
+        );
+    }
+
+    #[test]
+    fn test_try_gemm_errors() {
+        let kernel = TestKernel;
+
+        let a = [1; 2 * 3];
+        let b = [1; 3 * 2];
+        let a = MatRef::row_major(2, 3, &a);
+        let b = MatRef::row_major(3, 2, &b);
+        let mut c = [7; 2 * 2];
+        let mut c = MatMut::row_major(2, 2, &mut c);
+
+        let pack_sizes = PackSizes {
+            mc: 4,
+            kc: 2,
+            nc: 5,
+        };
+        let mut buf = vec![0; pack_sizes.buf_len()];
+
+        let result = kernel.try_gemm(1, b, b, 1, &mut c, pack_sizes, &mut buf);
+        let expect = GemmError::DimMismatch {
+            a: [3, 2],
+            b: [3, 2],
+            c: [2, 2],
+        };
+        assert_eq!(result, Err(expect));
+
+        let small = PackSizes {
+            mc: 3,
+            kc: 2,
+            nc: 5,
+        };
+        let result = kernel.try_gemm(1, a, b, 1, &mut c, small, &mut buf);
+        let expect = GemmError::PackSizesTooSmall {
+            pack_sizes: small,
+            mr: 4,
+            nr: 5,
+        };
+        assert_eq!(result, Err(expect));
+
+        let zero_kc = PackSizes {
+            mc: 4,
+            kc: 0,
+            nc: 5,
+        };
+        let result = kernel.try_gemm(1, a, b, 1, &mut c, zero_kc, &mut buf);
+        assert!(matches!(result, Err(GemmError::PackSizesTooSmall { .. })));
+
+        let huge = PackSizes {
+            mc: usize::MAX,
+            kc: 2,
+            nc: 5,
+        };
+        let result = kernel.try_gemm(1, a, b, 1, &mut c, huge, &mut buf);
+        assert_eq!(result, Err(GemmError::Overflow));
+
+        let result = kernel.try_gemm(1, a, b, 1, &mut c, pack_sizes, &mut buf[1..]);
+        let expect = GemmError::BufferLen {
+            expected: buf.len(),
+            got: buf.len() - 1,
+        };
+        assert_eq!(result, Err(expect));
+
+        assert_eq!(c.as_slice(), [7; 4]);
+        assert_eq!(
+            kernel.try_gemm(1, a, b, 1, &mut c, pack_sizes, &mut buf),
+            Ok(())
+        );
+        assert_eq!(c.as_slice(), [10; 4]);
+    }
+
+    #[test]
+    fn test_gemm_empty() {
+        let kernel = TestKernel;
+        let pack_sizes = PackSizes {
+            mc: 4,
+            kc: 2,
+            nc: 5,
+        };
+        let mut buf = vec![0; pack_sizes.buf_len()];
+
+        for [m, k, n] in [[0, 0, 0], [0, 3, 2], [2, 3, 0], [0, 0, 3], [3, 0, 0]] {
+            let a = vec![1; m * k];
+            let b = vec![1; k * n];
+            let a = MatRef::row_major(m, k, &a);
+            let b = MatRef::row_major(k, n, &b);
+            let mut c = vec![];
+            let mut c = MatMut::row_major(m, n, &mut c);
+            kernel.gemm(2, a, b, 3, &mut c, pack_sizes, &mut buf);
+            assert!(c.as_slice().is_empty());
+        }
+    }
+
+    #[test]
+    fn test_gemm_k_zero() {
+        let kernel = TestKernel;
+        let pack_sizes = PackSizes {
+            mc: 4,
+            kc: 2,
+            nc: 5,
+        };
+        let mut buf = vec![0; pack_sizes.buf_len()];
+
+        let [m, n] = [3, 7];
+        let a = MatRef::row_major(m, 0, &[]);
+        let b = MatRef::col_major(0, n, &[]);
+        let mut c = (0..m * n).map(|x| x as i32).collect::<Vec<_>>();
+        let mut expect = c.clone();
+        let mut c = MatMut::col_major(m, n, &mut c);
+        let mut expect = MatMut::col_major(m, n, &mut expect);
+
+        kernel.gemm(2, a, b, -3, &mut c, pack_sizes, &mut buf);
+        naive_gemm(2, a, b, -3, &mut expect);
+        assert_eq!(c.as_slice(), expect.as_slice());
+        assert!(c
+            .as_slice()
+            .iter()
+            .enumerate()
+            .all(|(i, &x)| x == -3 * i as i32));
+    }
+
+    #[test]
+    fn test_gemm_beta_zero_overwrites_nan() {
+        let kernel = GenericKernel4x4::<f32>::new();
+        let [m, k, n] = [7, 9, 6];
+        let a = (0..m * k).map(|x| x as f32).collect::<Vec<_>>();
+        let b = (0..k * n).map(|x| 1.0 - x as f32).collect::<Vec<_>>();
+        let a = MatRef::row_major(m, k, &a);
+        let b = MatRef::col_major(k, n, &b);
+
+        let mut expect = vec![0.0; m * n];
+        let mut expect = MatMut::row_major(m, n, &mut expect);
+        naive_gemm(2.0, a, b, 0.0, &mut expect);
+
+        let pack_sizes = PackSizes {
+            mc: 4,
+            kc: 2,
+            nc: 4,
+        };
+        let mut buf = vec![f32::NAN; pack_sizes.buf_len()];
+        let mut dst_buf = [f32::NAN; 16];
+
+        for garbage in [f32::NAN, f32::INFINITY, f32::NEG_INFINITY] {
+            let mut c = vec![garbage; m * n];
+            let mut c = MatMut::row_major(m, n, &mut c);
+            kernel.gemm(2.0, a, b, 0.0, &mut c, pack_sizes, &mut buf);
+            assert_eq!(c.as_slice(), expect.as_slice());
+
+            let mut c = vec![garbage; m * n];
+            let mut c = MatMut::row_major(m, n, &mut c);
+            gemm_dyn(
+                &kernel,
+                2.0,
+                a,
+                b,
+                0.0,
+                &mut c,
+                pack_sizes,
+                &mut buf,
+                &mut dst_buf,
+            );
+            assert_eq!(c.as_slice(), expect.as_slice());
+
+            let [a, b] = [MatRef::row_major(m, 0, &[]), MatRef::row_major(0, n, &[])];
+            let mut c = vec![garbage; m * n];
+            let mut c = MatMut::row_major(m, n, &mut c);
+            kernel.gemm(2.0, a, b, 0.0, &mut c, pack_sizes, &mut buf);
+            assert!(c.as_slice().iter().all(|&x| x == 0.0));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "dimension mismatch")]
+    fn test_gemm_panics_on_dim_mismatch() {
+        let a = MatRef::row_major(1, 2, &[1, 2]);
+        let mut c = [0];
+        let mut c = MatMut::row_major(1, 1, &mut c);
+
+        let pack_sizes = PackSizes {
+            mc: 4,
+            kc: 1,
+            nc: 5,
+        };
+        let mut buf = vec![0; pack_sizes.buf_len()];
+        TestKernel.gemm(1, a, a, 0, &mut c, pack_sizes, &mut buf);
+    }
+
+    #[test]
+    fn test_gemm_in_place_col_major_submatrix() {
+        let kernel = GenericKernel4x4::<i32>::new();
+        assert!(kernel.dyn_strided_dst());
+        let [m, k, n] = [9, 5, 10];
+        let a = (0..m * k).map(|x| x as i32 % 7 - 3).collect::<Vec<_>>();
+        let b = (0..k * n).map(|x| x as i32 % 5 - 2).collect::<Vec<_>>();
+        let a = MatRef::row_major(m, k, &a);
+        let b = MatRef::row_major(k, n, &b);
+
+        let pack_sizes = PackSizes {
+            mc: 8,
+            kc: 3,
+            nc: 8,
+        };
+        let mut buf = vec![-1; pack_sizes.buf_len()];
+
+        // c is the submatrix [1..m + 1, 2..n + 2] of a col-major matrix
+        let [rows, cols] = [m + 3, n + 4];
+        for beta in [0, -3] {
+            let mut values = (0..rows * cols).map(|x| x as i32).collect::<Vec<_>>();
+            let mut expect = values.clone();
+            let mut expect = MatMut::col_major(rows, cols, &mut expect);
+            naive_gemm(2, a, b, beta, &mut expect.submatrix_mut(1..m + 1, 2..n + 2));
+
+            let mut mat = MatMut::col_major(rows, cols, &mut values);
+            let mut c = mat.submatrix_mut(1..m + 1, 2..n + 2);
+            kernel.gemm(2, a, b, beta, &mut c, pack_sizes, &mut buf);
+            assert_eq!(mat.as_slice(), expect.as_slice());
+        }
+    }
 }
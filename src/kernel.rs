@@ -1,4 +1,11 @@
-use crate::{gemm_with_kernel, MatMut, MatRef, PackSizes};
+use crate::batched::{try_gemm_batched, try_gemm_strided_batched};
+use crate::executor::try_gemm_with_executor;
+use crate::mixed::{try_gemm_mixed, try_gemm_mixed_into};
+use crate::prepacked::{try_gemm_prepacked_a, try_gemm_prepacked_b};
+use crate::{
+    gemm_with_kernel, try_gemm_with_kernel, Epilogue, Executor, GemmError, MatMut, MatRef,
+    PackSizes, PackedA, PackedB,
+};
 use core::ops::Mul;
 use generic_array::{
     typenum::{Prod, Unsigned},
@@ -6,9 +13,6 @@ use generic_array::{
 };
 use num_traits::{One, Zero};
 
-#[cfg(test)]
-use allocator_api2::alloc::Allocator;
-
 pub trait Kernel
 where
     Self::Scalar: Copy + Zero + One,
@@ -20,6 +24,15 @@ where
     const MR: usize = Self::Mr::USIZE;
     const NR: usize = Self::Nr::USIZE;
 
+    /// Whether the microkernel accepts a strided `dst`,
+    /// i.e. any `dst.col_stride() >= MR` instead of a dense col-major `MR x NR` tile.
+    ///
+    /// If `true`, the driver computes full tiles of a `c` with `row_stride() == 1`
+    /// in place, without copying them to and from a scratch tile.
+    /// Only the `MR x NR` elements of `dst` may be read or written,
+    /// the values between its columns belong to other tiles of `c`.
+    const STRIDED_DST: bool = false;
+
     fn microkernel(
         &self,
         alpha: Self::Scalar,
@@ -29,6 +42,28 @@ where
         dst: &mut MatMut<Self::Scalar>,
     );
 
+    /// `dst <- alpha lhs rhs`, the values of `dst` are never read.
+    ///
+    /// Used by `gemm` when `beta` is zero, so that NaN or infinite values in `c`
+    /// are overwritten as in reference BLAS.
+    /// The default implementation zeroes `dst` and calls [`microkernel`](Self::microkernel).
+    #[inline]
+    fn microkernel_overwrite(
+        &self,
+        alpha: Self::Scalar,
+        lhs: MatRef<Self::Scalar>,
+        rhs: MatRef<Self::Scalar>,
+        dst: &mut MatMut<Self::Scalar>,
+    ) {
+        let zero = Self::Scalar::zero();
+        for col in 0..dst.ncols() {
+            for row in 0..dst.nrows() {
+                *dst.get_mut(row, col) = zero;
+            }
+        }
+        self.microkernel(alpha, lhs, rhs, zero, dst);
+    }
+
     #[inline]
     #[allow(clippy::too_many_arguments)]
     fn gemm(
@@ -44,24 +79,597 @@ where
         gemm_with_kernel(self, alpha, a, b, beta, c, pack_sizes, packing_buf);
     }
 
-    #[cfg(test)]
+    /// Fallible version of [`gemm`](Self::gemm).
+    ///
+    /// Returns an error instead of panicking if the dimensions of the matrices do not match,
+    /// `pack_sizes` is too small for the kernel or `packing_buf` has an invalid length.
+    /// `c` is not modified if an error is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use microgemm::{kernels::GenericKernel2x2, GemmError, Kernel as _, MatMut, MatRef, PackSizes};
+    ///
+    /// let kernel = GenericKernel2x2::<i32>::new();
+    /// let a = MatRef::row_major(2, 3, &[1, 2, 3, 4, 5, 6]);
+    /// let b = MatRef::row_major(2, 2, &[1, 2, 3, 4]);
+    /// let mut c = [0; 2 * 2];
+    /// let mut c = MatMut::row_major(2, 2, &mut c);
+    ///
+    /// let pack_sizes = PackSizes { mc: 2, kc: 2, nc: 2 };
+    /// let mut packing_buf = [0; PackSizes { mc: 2, kc: 2, nc: 2 }.buf_len()];
+    ///
+    /// let result = kernel.try_gemm(1, a, b, 0, &mut c, pack_sizes, &mut packing_buf);
+    /// assert_eq!(
+    ///     result,
+    ///     Err(GemmError::DimMismatch { a: [2, 3], b: [2, 2], c: [2, 2] })
+    /// );
+    /// ```
+    #[inline]
+    #[allow(clippy::too_many_arguments)]
+    fn try_gemm(
+        &self,
+        alpha: Self::Scalar,
+        a: MatRef<Self::Scalar>,
+        b: MatRef<Self::Scalar>,
+        beta: Self::Scalar,
+        c: &mut MatMut<Self::Scalar>,
+        pack_sizes: PackSizes,
+        packing_buf: &mut [Self::Scalar],
+    ) -> Result<(), GemmError> {
+        let epilogue = None::<&mut dyn Epilogue<Self::Scalar>>;
+        try_gemm_with_kernel(
+            self,
+            alpha,
+            a,
+            b,
+            beta,
+            c,
+            pack_sizes,
+            packing_buf,
+            epilogue,
+        )
+    }
+
+    /// Computes `c <- alpha a b + beta c` for `a` and `b` of other scalar types,
+    /// such as `half::f16` or `half::bf16` for a kernel of `f32`.
+    ///
+    /// `a` and `b` are converted to `Self::Scalar` while they are packed,
+    /// so the microkernel accumulates and writes `c` in `Self::Scalar`
+    /// instead of losing precision in the narrow type for large `k`.
+    /// `packing_buf` holds `Self::Scalar` as in [`gemm`](Self::gemm).
+    ///
+    /// The microkernel multiplies converted values, and `alpha`, `beta` and `c`
+    /// are of `Self::Scalar`. See [`gemm_mixed_into`](Self::gemm_mixed_into)
+    /// for a `c` of another type.
+    ///
+    /// # Panics
+    /// On the same conditions as [`gemm`](Self::gemm).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use half::{bf16, f16};
+    /// use microgemm::{kernels::GenericKernel2x2, Kernel as _, MatMut, MatRef, PackSizes};
+    ///
+    /// let kernel = GenericKernel2x2::<f32>::new();
+    /// let pack_sizes = PackSizes { mc: 2, kc: 2, nc: 2 };
+    /// let mut packing_buf = [0.0; PackSizes { mc: 2, kc: 2, nc: 2 }.buf_len()];
+    ///
+    /// // the sum 2048 + 1 + 1 rounds to 2048 in f16, but not in f32
+    /// let a = [2048.0, 1.0, 1.0].map(f16::from_f32);
+    /// let b = [1.0; 3].map(bf16::from_f32);
+    /// let a = MatRef::row_major(1, 3, &a);
+    /// let b = MatRef::row_major(3, 1, &b);
+    /// let mut c = [0.0];
+    /// let mut c = MatMut::row_major(1, 1, &mut c);
+    ///
+    /// kernel.gemm_mixed(1.0, a, b, 0.0, &mut c, pack_sizes, &mut packing_buf);
+    /// assert_eq!(c.as_slice(), [2050.0]);
+    /// ```
+    #[inline]
+    #[allow(clippy::too_many_arguments)]
+    fn gemm_mixed<A, B>(
+        &self,
+        alpha: Self::Scalar,
+        a: MatRef<A>,
+        b: MatRef<B>,
+        beta: Self::Scalar,
+        c: &mut MatMut<Self::Scalar>,
+        pack_sizes: PackSizes,
+        packing_buf: &mut [Self::Scalar],
+    ) where
+        A: Copy + Into<Self::Scalar>,
+        B: Copy + Into<Self::Scalar>,
+    {
+        let result = try_gemm_mixed(self, alpha, a, b, beta, c, pack_sizes, packing_buf);
+        if let Err(err) = result {
+            panic!("{err}");
+        }
+    }
+
+    /// Fallible version of [`gemm_mixed`](Self::gemm_mixed), see [`try_gemm`](Self::try_gemm).
+    #[inline]
+    #[allow(clippy::too_many_arguments)]
+    fn try_gemm_mixed<A, B>(
+        &self,
+        alpha: Self::Scalar,
+        a: MatRef<A>,
+        b: MatRef<B>,
+        beta: Self::Scalar,
+        c: &mut MatMut<Self::Scalar>,
+        pack_sizes: PackSizes,
+        packing_buf: &mut [Self::Scalar],
+    ) -> Result<(), GemmError>
+    where
+        A: Copy + Into<Self::Scalar>,
+        B: Copy + Into<Self::Scalar>,
+    {
+        try_gemm_mixed(self, alpha, a, b, beta, c, pack_sizes, packing_buf)
+    }
+
+    /// Computes `c <- alpha a b + beta c` like [`gemm_mixed`](Self::gemm_mixed),
+    /// for a `c` of another scalar type `O`, such as `f64` for a kernel of `f32`.
+    ///
+    /// Each tile of `alpha a b` is accumulated in `Self::Scalar` over all of `k`,
+    /// then converted to `O` when it is written back to `c`, where `beta c` is added in `O`.
+    /// If a block of `k` columns of `a` and `k` rows of `b` does not fit in `packing_buf`,
+    /// the tiles are computed one at a time, repacking `b` for each tile row.
+    ///
+    /// # Panics
+    /// On the same conditions as [`gemm`](Self::gemm).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use half::f16;
+    /// use microgemm::{kernels::GenericKernel2x2, Kernel as _, MatMut, MatRef, PackSizes};
+    ///
+    /// let kernel = GenericKernel2x2::<f32>::new();
+    /// let pack_sizes = PackSizes { mc: 2, kc: 2, nc: 2 };
+    /// let mut packing_buf = [0.0; PackSizes { mc: 2, kc: 2, nc: 2 }.buf_len()];
+    ///
+    /// let a = [1.0, 2.0, 3.0].map(f16::from_f32);
+    /// let b = [4.0, 5.0, 6.0].map(f16::from_f32);
+    /// let a = MatRef::row_major(1, 3, &a);
+    /// let b = MatRef::row_major(3, 1, &b);
+    /// // 1e9 + 32 is exact in f64, but not in f32
+    /// let mut c = [1e9f64];
+    /// let mut c = MatMut::row_major(1, 1, &mut c);
+    ///
+    /// kernel.gemm_mixed_into(1.0, a, b, 1.0, &mut c, pack_sizes, &mut packing_buf);
+    /// assert_eq!(c.as_slice(), [1e9 + 32.0]);
+    /// ```
+    #[inline]
+    #[allow(clippy::too_many_arguments)]
+    fn gemm_mixed_into<A, B, O>(
+        &self,
+        alpha: Self::Scalar,
+        a: MatRef<A>,
+        b: MatRef<B>,
+        beta: O,
+        c: &mut MatMut<O>,
+        pack_sizes: PackSizes,
+        packing_buf: &mut [Self::Scalar],
+    ) where
+        A: Copy + Into<Self::Scalar>,
+        B: Copy + Into<Self::Scalar>,
+        Self::Scalar: Into<O>,
+        O: Copy + Zero + One,
+    {
+        let result = try_gemm_mixed_into(self, alpha, a, b, beta, c, pack_sizes, packing_buf);
+        if let Err(err) = result {
+            panic!("{err}");
+        }
+    }
+
+    /// Fallible version of [`gemm_mixed_into`](Self::gemm_mixed_into),
+    /// see [`try_gemm`](Self::try_gemm).
+    #[inline]
+    #[allow(clippy::too_many_arguments)]
+    fn try_gemm_mixed_into<A, B, O>(
+        &self,
+        alpha: Self::Scalar,
+        a: MatRef<A>,
+        b: MatRef<B>,
+        beta: O,
+        c: &mut MatMut<O>,
+        pack_sizes: PackSizes,
+        packing_buf: &mut [Self::Scalar],
+    ) -> Result<(), GemmError>
+    where
+        A: Copy + Into<Self::Scalar>,
+        B: Copy + Into<Self::Scalar>,
+        Self::Scalar: Into<O>,
+        O: Copy + Zero + One,
+    {
+        try_gemm_mixed_into(self, alpha, a, b, beta, c, pack_sizes, packing_buf)
+    }
+
+    /// Computes `c <- epilogue(alpha a b + beta c)` in a single pass over `c`.
+    ///
+    /// The [`Epilogue`], which may also be a closure `FnMut(row, col, value) -> T`,
+    /// is applied exactly once to every element of `c` once its last `kc` block
+    /// is accumulated, before the tile is written back to `c`. Nothing is allocated.
+    ///
+    /// # Panics
+    /// 1. On the same conditions as [`gemm`](Self::gemm).
+    /// 2. If the epilogue panics, e.g. a bias shorter than `c`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use microgemm::{
+    ///     epilogue::{Clamp, RowBias},
+    ///     kernels::GenericKernel2x2,
+    ///     Epilogue as _, Kernel as _, MatMut, MatRef, PackSizes,
+    /// };
+    ///
+    /// let kernel = GenericKernel2x2::<i32>::new();
+    /// let pack_sizes = PackSizes { mc: 2, kc: 2, nc: 2 };
+    /// let mut packing_buf = [0; PackSizes { mc: 2, kc: 2, nc: 2 }.buf_len()];
+    ///
+    /// let a = MatRef::row_major(2, 2, &[1, 2, 3, 4]);
+    /// let b = MatRef::row_major(2, 1, &[1, 1]);
+    /// let mut c = [0; 2];
+    /// let mut c = MatMut::row_major(2, 1, &mut c);
+    ///
+    /// let epilogue = RowBias(&[10, 20]).then(Clamp { min: 0, max: 25 });
+    /// kernel.gemm_with_epilogue(1, a, b, 0, &mut c, pack_sizes, &mut packing_buf, epilogue);
+    /// assert_eq!(c.as_slice(), [13, 25]);
+    ///
+    /// // scales the rows by per-row factors
+    /// let scales = [2, -1];
+    /// let epilogue = |row: usize, _col: usize, x: i32| scales[row] * x;
+    /// kernel.gemm_with_epilogue(1, a, b, 0, &mut c, pack_sizes, &mut packing_buf, epilogue);
+    /// assert_eq!(c.as_slice(), [6, -7]);
+    /// ```
+    #[inline]
+    #[allow(clippy::too_many_arguments)]
+    fn gemm_with_epilogue<E>(
+        &self,
+        alpha: Self::Scalar,
+        a: MatRef<Self::Scalar>,
+        b: MatRef<Self::Scalar>,
+        beta: Self::Scalar,
+        c: &mut MatMut<Self::Scalar>,
+        pack_sizes: PackSizes,
+        packing_buf: &mut [Self::Scalar],
+        epilogue: E,
+    ) where
+        E: Epilogue<Self::Scalar>,
+    {
+        let result =
+            self.try_gemm_with_epilogue(alpha, a, b, beta, c, pack_sizes, packing_buf, epilogue);
+        if let Err(err) = result {
+            panic!("{err}");
+        }
+    }
+
+    /// Fallible version of [`gemm_with_epilogue`](Self::gemm_with_epilogue),
+    /// see [`try_gemm`](Self::try_gemm).
+    #[inline]
+    #[allow(clippy::too_many_arguments)]
+    fn try_gemm_with_epilogue<E>(
+        &self,
+        alpha: Self::Scalar,
+        a: MatRef<Self::Scalar>,
+        b: MatRef<Self::Scalar>,
+        beta: Self::Scalar,
+        c: &mut MatMut<Self::Scalar>,
+        pack_sizes: PackSizes,
+        packing_buf: &mut [Self::Scalar],
+        mut epilogue: E,
+    ) -> Result<(), GemmError>
+    where
+        E: Epilogue<Self::Scalar>,
+    {
+        let epilogue = Some(&mut epilogue);
+        try_gemm_with_kernel(
+            self,
+            alpha,
+            a,
+            b,
+            beta,
+            c,
+            pack_sizes,
+            packing_buf,
+            epilogue,
+        )
+    }
+
+    /// Computes `c[i] <- alpha a[i] b[i] + beta c[i]` for every `i` of the batch.
+    ///
+    /// The arguments are checked once, before any of `c` is modified,
+    /// and `packing_buf` is reused for the whole batch.
+    /// If all of `b` are the same view, `b` is packed only once, as by [`PackedB`],
+    /// into `packing_buf` of [`PackSizes::batched_buf_len`], or of `pack_sizes.buf_len()`
+    /// if the packed `b` fits into a single `kc x nc` panel.
+    /// Otherwise `packing_buf` must be of `pack_sizes.buf_len()`.
+    ///
+    /// # Panics
+    /// 1. If `a`, `b` and `c` have different lengths.
+    /// 2. On the same conditions as [`gemm`](Self::gemm) for any of the triples.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use microgemm::{kernels::GenericKernel2x2, Kernel as _, MatMut, MatRef, PackSizes};
+    ///
+    /// let kernel = GenericKernel2x2::<i32>::new();
+    /// let pack_sizes = PackSizes { mc: 2, kc: 2, nc: 2 };
+    /// let mut packing_buf = [0; PackSizes { mc: 2, kc: 2, nc: 2 }.buf_len()];
+    ///
+    /// let a = [MatRef::row_major(1, 2, &[1, 2]), MatRef::row_major(1, 2, &[3, 4])];
+    /// let b = [MatRef::row_major(2, 1, &[10, 100]); 2];
+    /// let mut c = [[0], [0]];
+    /// let [c0, c1] = &mut c;
+    /// let mut c = [MatMut::row_major(1, 1, c0), MatMut::row_major(1, 1, c1)];
+    ///
+    /// kernel.gemm_batched(1, &a, &b, 0, &mut c, pack_sizes, &mut packing_buf);
+    /// assert_eq!([c[0].as_slice(), c[1].as_slice()], [[210], [430]]);
+    /// ```
+    #[inline]
     #[allow(clippy::too_many_arguments)]
-    fn gemm_in(
+    fn gemm_batched(
         &self,
-        alloc: impl Allocator,
+        alpha: Self::Scalar,
+        a: &[MatRef<Self::Scalar>],
+        b: &[MatRef<Self::Scalar>],
+        beta: Self::Scalar,
+        c: &mut [MatMut<Self::Scalar>],
+        pack_sizes: PackSizes,
+        packing_buf: &mut [Self::Scalar],
+    ) {
+        let result = try_gemm_batched(self, alpha, a, b, beta, c, pack_sizes, packing_buf);
+        if let Err(err) = result {
+            panic!("{err}");
+        }
+    }
+
+    /// Computes `c[i] <- alpha a[i] b[i] + beta c[i]` for `batch_count` matrices
+    /// stored at fixed offsets in the values of each operand, like `gemmStridedBatched` of cuBLAS.
+    ///
+    /// Unlike cuBLAS, which takes a pointer and a leading dimension for each operand,
+    /// `a`, `b` and `c` are views of the first matrix of the batch whose values hold the whole batch:
+    /// the `i`-th matrix of `a` has the same layout and starts at `i * stride_a` of `a.as_slice()`,
+    /// and so on. For example, `batch_count` row-major `m x k` matrices stored one after another
+    /// in `values` are passed as `MatRef::from_parts(m, k, &values, k, 1)` with `stride_a = m * k`.
+    ///
+    /// A stride of zero broadcasts the same `a` or `b` to the whole batch. A broadcast `b` is packed
+    /// only once, as by [`PackedB`], into `packing_buf` of [`PackSizes::batched_buf_len`],
+    /// or of `pack_sizes.buf_len()` if the packed `b` fits into a single `kc x nc` panel.
+    /// Otherwise `packing_buf` must be of `pack_sizes.buf_len()`.
+    ///
+    /// # Panics
+    /// 1. On the same conditions as [`gemm`](Self::gemm).
+    /// 2. If a matrix of the batch is out of the values of its operand.
+    /// 3. If the matrices of `c` overlap, i.e. `stride_c` is less than the span of `c`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use microgemm::{kernels::GenericKernel2x2, Kernel as _, MatMut, MatRef, PackSizes};
+    ///
+    /// let kernel = GenericKernel2x2::<i32>::new();
+    /// let pack_sizes = PackSizes { mc: 2, kc: 2, nc: 2 };
+    /// let mut packing_buf = [0; PackSizes { mc: 2, kc: 2, nc: 2 }.buf_len()];
+    ///
+    /// // three 1x2 matrices of a and a single 2x1 matrix b, broadcast with stride 0
+    /// let a = [1, 2, 3, 4, 5, 6];
+    /// let a = MatRef::from_parts(1, 2, &a[..], 2, 1).unwrap();
+    /// let b = MatRef::row_major(2, 1, &[10, 100]);
+    /// let mut c = [0; 3];
+    /// let mut c = MatMut::from_parts(1, 1, &mut c[..], 1, 1).unwrap();
+    ///
+    /// kernel.gemm_strided_batched(3, 1, a, 2, b, 0, 0, &mut c, 1, pack_sizes, &mut packing_buf);
+    /// assert_eq!(c.as_slice(), [210, 430, 650]);
+    /// ```
+    #[inline]
+    #[allow(clippy::too_many_arguments)]
+    fn gemm_strided_batched(
+        &self,
+        batch_count: usize,
         alpha: Self::Scalar,
         a: MatRef<Self::Scalar>,
+        stride_a: usize,
         b: MatRef<Self::Scalar>,
+        stride_b: usize,
         beta: Self::Scalar,
         c: &mut MatMut<Self::Scalar>,
+        stride_c: usize,
         pack_sizes: PackSizes,
+        packing_buf: &mut [Self::Scalar],
     ) {
-        use allocator_api2::vec::Vec;
+        let result = try_gemm_strided_batched(
+            self,
+            batch_count,
+            alpha,
+            a,
+            stride_a,
+            b,
+            stride_b,
+            beta,
+            c,
+            stride_c,
+            pack_sizes,
+            packing_buf,
+        );
+        if let Err(err) = result {
+            panic!("{err}");
+        }
+    }
+
+    /// Computes `c <- alpha a b + beta c` with the tasks run by a user-supplied [`Executor`].
+    ///
+    /// For each `kc x nc` panel of `b`, packed once and shared by the workers,
+    /// [`Executor::run`] runs a task on each worker and must return when all of them are finished.
+    /// The workers compute the ic loop over blocks of whole `mr x nr` tiles of `c`,
+    /// each packing its blocks of `a` into its own `mc x kc` buffer.
+    /// The blocks are made of rows of `c`, or of columns if the rows interleave in memory,
+    /// e.g. for a col-major `c`. If neither can be split, `c` is computed by a single worker.
+    /// Nothing is allocated: `workspace` holds the shared panel of `b` and the buffers
+    /// of the workers, see [`PackSizes::par_buf_len`].
+    ///
+    /// # Panics
+    /// 1. On the same conditions as [`gemm`](Self::gemm).
+    /// 2. If `workspace.len() != pack_sizes.par_buf_len(executor.workers())`.
+    /// 3. If `executor.workers()` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use microgemm::{kernels::GenericKernel2x2, Executor, Kernel as _, MatMut, MatRef, PackSizes};
+    ///
+    /// struct Sequential;
+    ///
+    /// impl Executor for Sequential {
+    ///     fn workers(&self) -> usize {
+    ///         2
+    ///     }
+    ///
+    ///     fn run(&self, task: &(dyn Fn() + Sync)) {
+    ///         task();
+    ///         task();
+    ///     }
+    /// }
+    ///
+    /// let kernel = GenericKernel2x2::<i32>::new();
+    /// let pack_sizes = PackSizes { mc: 2, kc: 2, nc: 2 };
+    /// let mut workspace = [0; PackSizes { mc: 2, kc: 2, nc: 2 }.par_buf_len(2)];
+    ///
+    /// let a = MatRef::row_major(3, 2, &[1, 2, 3, 4, 5, 6]);
+    /// let b = MatRef::row_major(2, 1, &[10, 100]);
+    /// let mut c = [0; 3];
+    /// let mut c = MatMut::row_major(3, 1, &mut c);
+    ///
+    /// kernel.gemm_with_executor(&Sequential, 1, a, b, 0, &mut c, pack_sizes, &mut workspace);
+    /// assert_eq!(c.as_slice(), [210, 430, 650]);
+    /// ```
+    #[inline]
+    #[allow(clippy::too_many_arguments)]
+    fn gemm_with_executor<E>(
+        &self,
+        executor: &E,
+        alpha: Self::Scalar,
+        a: MatRef<Self::Scalar>,
+        b: MatRef<Self::Scalar>,
+        beta: Self::Scalar,
+        c: &mut MatMut<Self::Scalar>,
+        pack_sizes: PackSizes,
+        workspace: &mut [Self::Scalar],
+    ) where
+        Self: Sync,
+        Self::Scalar: Send + Sync,
+        E: Executor + ?Sized,
+    {
+        let result =
+            try_gemm_with_executor(self, executor, alpha, a, b, beta, c, pack_sizes, workspace);
+        if let Err(err) = result {
+            panic!("{err}");
+        }
+    }
+
+    /// Computes `c <- alpha a b + beta c` with `b` packed beforehand by [`PackedB`],
+    /// so that the packing of `b` is skipped.
+    ///
+    /// The pack sizes are the ones `b` was packed with,
+    /// `packing_buf` is only used to pack `a` and must have length `pack_sizes.mc * pack_sizes.kc`.
+    ///
+    /// # Panics
+    /// 1. If `b` was packed for a kernel with a different `NR`.
+    /// 2. If the dimensions of `a`, `b` and `c` do not match.
+    /// 3. If `b.pack_sizes().mc < Self::MR` or `packing_buf` has an invalid length.
+    #[inline]
+    fn gemm_prepacked_b<V>(
+        &self,
+        alpha: Self::Scalar,
+        a: MatRef<Self::Scalar>,
+        b: &PackedB<V, Self::Scalar>,
+        beta: Self::Scalar,
+        c: &mut MatMut<Self::Scalar>,
+        packing_buf: &mut [Self::Scalar],
+    ) where
+        V: AsRef<[Self::Scalar]>,
+    {
+        let result = try_gemm_prepacked_b(self, alpha, a, b, beta, c, packing_buf);
+        if let Err(err) = result {
+            panic!("{err}");
+        }
+    }
+
+    /// Computes `c <- alpha a b + beta c` with `a` packed beforehand by [`PackedA`],
+    /// so that the packing of `a` is skipped.
+    ///
+    /// The pack sizes are the ones `a` was packed with,
+    /// `packing_buf` is only used to pack `b` and must have length `pack_sizes.kc * pack_sizes.nc`.
+    ///
+    /// # Panics
+    /// 1. If `a` was packed for a kernel with a different `MR`.
+    /// 2. If the dimensions of `a`, `b` and `c` do not match.
+    /// 3. If `a.pack_sizes().nc < Self::NR` or `packing_buf` has an invalid length.
+    #[inline]
+    fn gemm_prepacked_a<V>(
+        &self,
+        alpha: Self::Scalar,
+        a: &PackedA<V, Self::Scalar>,
+        b: MatRef<Self::Scalar>,
+        beta: Self::Scalar,
+        c: &mut MatMut<Self::Scalar>,
+        packing_buf: &mut [Self::Scalar],
+    ) where
+        V: AsRef<[Self::Scalar]>,
+    {
+        let result = try_gemm_prepacked_a(self, alpha, a, b, beta, c, packing_buf);
+        if let Err(err) = result {
+            panic!("{err}");
+        }
+    }
 
-        let size = pack_sizes.buf_len();
-        let mut v = Vec::with_capacity_in(size, alloc);
-        v.resize(size, Self::Scalar::zero());
-        self.gemm(alpha, a, b, beta, c, pack_sizes, v.as_mut_slice());
+    /// Parallel version of [`gemm`](Self::gemm), running on the current
+    /// [rayon](https://docs.rs/rayon) thread pool.
+    ///
+    /// Each `kc x nc` panel of `b` is packed once and shared by the threads, which compute
+    /// the ic loop in parallel over blocks of whole `mr x nr` tiles made of rows of `c`,
+    /// or of columns if the rows interleave in memory, each packing its own blocks of `a`.
+    /// If neither can be split, `c` is computed by a single thread.
+    /// The packing buffers are allocated internally: one for the panel of `b`
+    /// and one `mc x kc` buffer for `a` per thread.
+    ///
+    /// # Panics
+    /// On the same conditions as [`gemm`](Self::gemm), except for the buffer length.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use microgemm::{kernels::GenericKernel4x4, Kernel as _, MatMut, MatRef, PackSizes};
+    ///
+    /// let kernel = GenericKernel4x4::<i32>::new();
+    /// let pack_sizes = PackSizes { mc: 4, kc: 2, nc: 4 };
+    ///
+    /// let a = MatRef::row_major(2, 3, &[1, 2, 3, 4, 5, 6]);
+    /// let b = MatRef::row_major(3, 2, &[10, 11, 20, 21, 30, 31]);
+    /// let mut c = [0; 2 * 2];
+    /// let mut c = MatMut::row_major(2, 2, &mut c);
+    ///
+    /// kernel.par_gemm(1, a, b, 0, &mut c, pack_sizes);
+    /// assert_eq!(c.as_slice(), [140, 146, 320, 335]);
+    /// ```
+    #[cfg(feature = "rayon")]
+    #[inline]
+    fn par_gemm(
+        &self,
+        alpha: Self::Scalar,
+        a: MatRef<Self::Scalar>,
+        b: MatRef<Self::Scalar>,
+        beta: Self::Scalar,
+        c: &mut MatMut<Self::Scalar>,
+        pack_sizes: PackSizes,
+    ) where
+        Self: Sync,
+        Self::Scalar: Send + Sync,
+    {
+        crate::par::par_gemm_with_kernel(self, alpha, a, b, beta, c, pack_sizes);
     }
 
     fn mr(&self) -> usize {
@@ -72,6 +680,135 @@ where
     }
 }
 
+/// Object-safe counterpart of [`Kernel`].
+///
+/// Implemented for every [`Kernel`], so kernels with different `MR` and `NR`
+/// can be stored as `&dyn DynKernel<T>` and used with [`gemm_dyn`](crate::gemm_dyn).
+/// The methods are prefixed with `dyn_` to avoid ambiguity with the methods of [`Kernel`].
+///
+/// # Examples
+///
+/// ```
+/// use microgemm::{
+///     kernels::{GenericKernel2x2, GenericKernel4x4},
+///     DynKernel,
+/// };
+///
+/// let kernels: [&dyn DynKernel<f32>; 2] = [&GenericKernel2x2::new(), &GenericKernel4x4::new()];
+/// assert_eq!(kernels[0].dyn_mr(), 2);
+/// assert_eq!(kernels[1].dyn_nr(), 4);
+/// ```
+pub trait DynKernel<T> {
+    fn dyn_mr(&self) -> usize;
+    fn dyn_nr(&self) -> usize;
+
+    /// `dst <- alpha lhs rhs + beta dst`, where
+    /// `lhs` is a col-major `mr x kc` matrix,
+    /// `rhs` is a row-major `kc x nr` matrix and
+    /// `dst` is a col-major `mr x nr` matrix.
+    ///
+    /// # Panics
+    /// If `lhs.len() != mr * kc`, `rhs.len() != kc * nr` or `dst.len() != mr * nr`.
+    fn dyn_microkernel(&self, alpha: T, lhs: &[T], rhs: &[T], beta: T, dst: &mut [T]);
+
+    /// `dst <- alpha lhs rhs` without reading `dst`,
+    /// see [`Kernel::microkernel_overwrite`].
+    ///
+    /// # Panics
+    /// On the same conditions as [`dyn_microkernel`](Self::dyn_microkernel).
+    fn dyn_microkernel_overwrite(&self, alpha: T, lhs: &[T], rhs: &[T], dst: &mut [T]);
+
+    /// Whether the kernel accepts a strided `dst`, see [`Kernel::STRIDED_DST`].
+    fn dyn_strided_dst(&self) -> bool;
+
+    /// [`dyn_microkernel`](Self::dyn_microkernel) for a `dst` with
+    /// `row_stride == 1` and the given `col_stride >= mr`,
+    /// or [`dyn_microkernel_overwrite`](Self::dyn_microkernel_overwrite) if `beta` is zero.
+    ///
+    /// # Panics
+    /// 1. If the kernel does not accept a strided `dst`.
+    /// 2. If `col_stride < mr` or `dst.len() != (nr - 1) * col_stride + mr`.
+    fn dyn_microkernel_strided(
+        &self,
+        alpha: T,
+        lhs: &[T],
+        rhs: &[T],
+        beta: T,
+        dst: &mut [T],
+        col_stride: usize,
+    );
+}
+
+impl<K> DynKernel<K::Scalar> for K
+where
+    K: Kernel + ?Sized,
+{
+    #[inline]
+    fn dyn_mr(&self) -> usize {
+        K::MR
+    }
+    #[inline]
+    fn dyn_nr(&self) -> usize {
+        K::NR
+    }
+    #[inline]
+    fn dyn_microkernel(
+        &self,
+        alpha: K::Scalar,
+        lhs: &[K::Scalar],
+        rhs: &[K::Scalar],
+        beta: K::Scalar,
+        dst: &mut [K::Scalar],
+    ) {
+        let kc = lhs.len() / K::MR;
+        let lhs = MatRef::col_major(K::MR, kc, lhs);
+        let rhs = MatRef::row_major(kc, K::NR, rhs);
+        let mut dst = MatMut::col_major(K::MR, K::NR, dst);
+        Kernel::microkernel(self, alpha, lhs, rhs, beta, &mut dst);
+    }
+    #[inline]
+    fn dyn_microkernel_overwrite(
+        &self,
+        alpha: K::Scalar,
+        lhs: &[K::Scalar],
+        rhs: &[K::Scalar],
+        dst: &mut [K::Scalar],
+    ) {
+        let kc = lhs.len() / K::MR;
+        let lhs = MatRef::col_major(K::MR, kc, lhs);
+        let rhs = MatRef::row_major(kc, K::NR, rhs);
+        let mut dst = MatMut::col_major(K::MR, K::NR, dst);
+        Kernel::microkernel_overwrite(self, alpha, lhs, rhs, &mut dst);
+    }
+    #[inline]
+    fn dyn_strided_dst(&self) -> bool {
+        K::STRIDED_DST
+    }
+    #[inline]
+    fn dyn_microkernel_strided(
+        &self,
+        alpha: K::Scalar,
+        lhs: &[K::Scalar],
+        rhs: &[K::Scalar],
+        beta: K::Scalar,
+        dst: &mut [K::Scalar],
+        col_stride: usize,
+    ) {
+        assert!(K::STRIDED_DST);
+        assert!(col_stride >= K::MR);
+        assert_eq!(dst.len(), (K::NR - 1) * col_stride + K::MR);
+        let kc = lhs.len() / K::MR;
+        let lhs = MatRef::col_major(K::MR, kc, lhs);
+        let rhs = MatRef::row_major(kc, K::NR, rhs);
+        let mut dst = MatMut::from_parts(K::MR, K::NR, dst, 1, col_stride).unwrap();
+        if beta.is_zero() {
+            Kernel::microkernel_overwrite(self, alpha, lhs, rhs, &mut dst);
+        } else {
+            Kernel::microkernel(self, alpha, lhs, rhs, beta, &mut dst);
+        }
+    }
+}
+
 pub trait Multiply<Rhs> {
     type Output: ArrayLength;
 }
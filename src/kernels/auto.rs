@@ -0,0 +1,729 @@
+use crate::{
+    CacheInfo, Epilogue, Executor, GemmError, Kernel, MatMut, MatRef, One, PackSizes, PackedA,
+    PackedB, Zero,
+};
+use core::marker::PhantomData;
+
+#[cfg(target_arch = "x86_64")]
+use super::{
+    avx2::is_avx2_fma_available, Avx2FmaKernel8x4, Avx2FmaKernel8x8, Sse2Kernel4x2, Sse2Kernel4x4,
+};
+#[cfg(target_arch = "aarch64")]
+use super::{neon::is_neon_available, NeonKernel8x8};
+use super::{GenericKernel4x4, GenericKernel8x8};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Backend {
+    #[cfg(target_arch = "aarch64")]
+    Neon,
+    #[cfg(target_arch = "x86_64")]
+    Avx2Fma,
+    #[cfg(target_arch = "x86_64")]
+    Sse2,
+    Generic,
+}
+
+impl Backend {
+    fn is_available(self) -> bool {
+        match self {
+            #[cfg(target_arch = "aarch64")]
+            Self::Neon => is_neon_available(),
+            #[cfg(target_arch = "x86_64")]
+            Self::Avx2Fma => is_avx2_fma_available(),
+            #[cfg(target_arch = "x86_64")]
+            Self::Sse2 => true,
+            Self::Generic => true,
+        }
+    }
+}
+
+/// Kernel that selects the fastest microkernel available on the current CPU.
+///
+/// CPU features are detected once, at construction.
+/// With the `std` feature the detection is performed at runtime,
+/// otherwise only the compile-time target features are taken into account.
+///
+/// # Warning
+///
+/// Without the `std` feature there is no runtime detection: an ordinary `x86_64` build,
+/// which only enables `sse2`, selects the `sse2` microkernel even on a CPU with `avx2` and `fma`.
+/// In `no_std` builds, enable the `std` feature where possible or build with
+/// `-C target-feature=+avx2,+fma` (or `-C target-cpu=native`) for the target CPU,
+/// otherwise choose the microkernel explicitly, e.g. with
+/// [`Avx2FmaKernel8x8::new`](super::Avx2FmaKernel8x8::new) after checking the features.
+///
+/// Since the selected microkernel is only known at runtime, `AutoKernel` does not
+/// implement [`Kernel`](crate::Kernel), but forwards the drivers of `Kernel` to it.
+/// The values packed by [`pack_a_into`](Self::pack_a_into) and [`pack_b_into`](Self::pack_b_into)
+/// depend on the tile size of the selected microkernel, so they can only be used
+/// with the same `AutoKernel` or one that selected the same microkernel.
+///
+/// # Examples
+///
+/// ```
+/// use microgemm::{kernels::AutoKernel, MatMut, MatRef};
+///
+/// let kernel = AutoKernel::<f32>::new();
+/// let pack_sizes = kernel.pack_sizes();
+/// let mut packing_buf = vec![0.0; pack_sizes.buf_len()];
+///
+/// let a = MatRef::row_major(2, 3, &[1., 2., 3., 4., 5., 6.]);
+/// let b = MatRef::row_major(3, 2, &[10., 11., 20., 21., 30., 31.]);
+/// let mut c = [0.; 2 * 2];
+/// let mut c = MatMut::row_major(2, 2, &mut c);
+///
+/// kernel.gemm(1.0, a, b, 0.0, &mut c, pack_sizes, &mut packing_buf);
+/// assert_eq!(c.as_slice(), [140., 146., 320., 335.]);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct AutoKernel<T> {
+    backend: Backend,
+    pack_sizes: PackSizes,
+    marker: PhantomData<T>,
+}
+
+// Caches assumed when they cannot be detected, typical of current x86_64 and aarch64 cores.
+const FALLBACK_CACHE: CacheInfo = CacheInfo {
+    l1: 32 << 10,
+    l2: 256 << 10,
+    l3: 8 << 20,
+};
+
+fn cache_info() -> CacheInfo {
+    #[cfg(feature = "std")]
+    if let Some(cache) = CacheInfo::detect() {
+        return cache;
+    }
+    FALLBACK_CACHE
+}
+
+fn pack_sizes_for<K: Kernel>(_: &K) -> PackSizes {
+    PackSizes::for_kernel::<K>(cache_info())
+}
+
+// Evaluates `$body` with `$kernel` bound to the microkernel of the backend of `$self`.
+macro_rules! dispatch {
+    (
+        $self:ident,
+        [$($(#[$cfg:meta])* $backend:ident => $new:expr,)+],
+        |$kernel:ident| $body:expr
+    ) => {
+        match $self.backend {
+            $(
+                $(#[$cfg])*
+                Backend::$backend => {
+                    // SAFETY: the backend was selected only if it is available.
+                    let $kernel = unsafe { $new };
+                    $body
+                }
+            )+
+            #[allow(unreachable_patterns)]
+            _ => unreachable!(),
+        }
+    };
+}
+
+macro_rules! impl_auto_kernel {
+    (
+        $scalar:ty {
+            $(
+                $(#[$cfg:meta])*
+                $backend:ident => $kernel:expr,
+            )+
+        }
+    ) => {
+        #[allow(unused_unsafe)]
+        impl AutoKernel<$scalar> {
+            /// Detects CPU features and selects the fastest available microkernel.
+            ///
+            /// With the `std` feature the cache sizes are detected as well,
+            /// see [`pack_sizes`](Self::pack_sizes).
+            pub fn new() -> Self {
+                $(
+                    $(#[$cfg])*
+                    if Backend::$backend.is_available() {
+                        // SAFETY: the backend is available.
+                        let pack_sizes = pack_sizes_for(&unsafe { $kernel });
+                        return Self {
+                            backend: Backend::$backend,
+                            pack_sizes,
+                            marker: PhantomData,
+                        };
+                    }
+                )+
+                unreachable!("the generic backend is always available")
+            }
+            /// Returns the number of rows of the selected microkernel.
+            pub fn mr(&self) -> usize {
+                dispatch!(self, [$($(#[$cfg])* $backend => $kernel,)+], |kernel| kernel.mr())
+            }
+            /// Returns the number of columns of the selected microkernel.
+            pub fn nr(&self) -> usize {
+                dispatch!(self, [$($(#[$cfg])* $backend => $kernel,)+], |kernel| kernel.nr())
+            }
+            /// Returns `PackSizes` recommended for the selected microkernel,
+            /// see [`PackSizes::for_kernel`].
+            ///
+            /// The cache sizes are given by `CacheInfo::detect` with the `std` feature,
+            /// when it succeeds. Otherwise 32 KiB of L1, 256 KiB of L2 and 8 MiB of L3 are assumed.
+            pub fn pack_sizes(&self) -> PackSizes {
+                self.pack_sizes
+            }
+            /// Computes `c <- alpha a b + beta c` with the selected microkernel.
+            ///
+            /// # Panics
+            /// Panics on the same conditions as [`Kernel::gemm`](crate::Kernel::gemm),
+            /// in particular if `pack_sizes` is smaller than the selected
+            /// microkernel (see [`mr`](Self::mr) and [`nr`](Self::nr)).
+            #[allow(clippy::too_many_arguments)]
+            pub fn gemm(
+                &self,
+                alpha: $scalar,
+                a: MatRef<$scalar>,
+                b: MatRef<$scalar>,
+                beta: $scalar,
+                c: &mut MatMut<$scalar>,
+                pack_sizes: PackSizes,
+                packing_buf: &mut [$scalar],
+            ) {
+                dispatch!(self, [$($(#[$cfg])* $backend => $kernel,)+], |kernel| {
+                    kernel.gemm(alpha, a, b, beta, c, pack_sizes, packing_buf)
+                })
+            }
+            /// Fallible version of [`gemm`](Self::gemm),
+            /// see [`Kernel::try_gemm`](crate::Kernel::try_gemm).
+            #[allow(clippy::too_many_arguments)]
+            pub fn try_gemm(
+                &self,
+                alpha: $scalar,
+                a: MatRef<$scalar>,
+                b: MatRef<$scalar>,
+                beta: $scalar,
+                c: &mut MatMut<$scalar>,
+                pack_sizes: PackSizes,
+                packing_buf: &mut [$scalar],
+            ) -> Result<(), GemmError> {
+                dispatch!(self, [$($(#[$cfg])* $backend => $kernel,)+], |kernel| {
+                    kernel.try_gemm(alpha, a, b, beta, c, pack_sizes, packing_buf)
+                })
+            }
+            /// [`Kernel::gemm_with_epilogue`](crate::Kernel::gemm_with_epilogue)
+            /// with the selected microkernel.
+            #[allow(clippy::too_many_arguments)]
+            pub fn gemm_with_epilogue<E>(
+                &self,
+                alpha: $scalar,
+                a: MatRef<$scalar>,
+                b: MatRef<$scalar>,
+                beta: $scalar,
+                c: &mut MatMut<$scalar>,
+                pack_sizes: PackSizes,
+                packing_buf: &mut [$scalar],
+                epilogue: E,
+            ) where
+                E: Epilogue<$scalar>,
+            {
+                dispatch!(self, [$($(#[$cfg])* $backend => $kernel,)+], |kernel| {
+                    kernel.gemm_with_epilogue(alpha, a, b, beta, c, pack_sizes, packing_buf, epilogue)
+                })
+            }
+            /// Fallible version of [`gemm_with_epilogue`](Self::gemm_with_epilogue),
+            /// see [`Kernel::try_gemm_with_epilogue`](crate::Kernel::try_gemm_with_epilogue).
+            #[allow(clippy::too_many_arguments)]
+            pub fn try_gemm_with_epilogue<E>(
+                &self,
+                alpha: $scalar,
+                a: MatRef<$scalar>,
+                b: MatRef<$scalar>,
+                beta: $scalar,
+                c: &mut MatMut<$scalar>,
+                pack_sizes: PackSizes,
+                packing_buf: &mut [$scalar],
+                epilogue: E,
+            ) -> Result<(), GemmError>
+            where
+                E: Epilogue<$scalar>,
+            {
+                dispatch!(self, [$($(#[$cfg])* $backend => $kernel,)+], |kernel| {
+                    kernel.try_gemm_with_epilogue(alpha, a, b, beta, c, pack_sizes, packing_buf, epilogue)
+                })
+            }
+            /// [`Kernel::gemm_batched`](crate::Kernel::gemm_batched)
+            /// with the selected microkernel.
+            #[allow(clippy::too_many_arguments)]
+            pub fn gemm_batched(
+                &self,
+                alpha: $scalar,
+                a: &[MatRef<$scalar>],
+                b: &[MatRef<$scalar>],
+                beta: $scalar,
+                c: &mut [MatMut<$scalar>],
+                pack_sizes: PackSizes,
+                packing_buf: &mut [$scalar],
+            ) {
+                dispatch!(self, [$($(#[$cfg])* $backend => $kernel,)+], |kernel| {
+                    kernel.gemm_batched(alpha, a, b, beta, c, pack_sizes, packing_buf)
+                })
+            }
+            /// [`Kernel::gemm_strided_batched`](crate::Kernel::gemm_strided_batched)
+            /// with the selected microkernel.
+            #[allow(clippy::too_many_arguments)]
+            pub fn gemm_strided_batched(
+                &self,
+                batch_count: usize,
+                alpha: $scalar,
+                a: MatRef<$scalar>,
+                stride_a: usize,
+                b: MatRef<$scalar>,
+                stride_b: usize,
+                beta: $scalar,
+                c: &mut MatMut<$scalar>,
+                stride_c: usize,
+                pack_sizes: PackSizes,
+                packing_buf: &mut [$scalar],
+            ) {
+                dispatch!(self, [$($(#[$cfg])* $backend => $kernel,)+], |kernel| {
+                    kernel.gemm_strided_batched(
+                        batch_count, alpha, a, stride_a, b, stride_b, beta, c, stride_c,
+                        pack_sizes, packing_buf,
+                    )
+                })
+            }
+            /// [`Kernel::gemm_mixed`](crate::Kernel::gemm_mixed) with the selected microkernel.
+            #[allow(clippy::too_many_arguments)]
+            pub fn gemm_mixed<A, B>(
+                &self,
+                alpha: $scalar,
+                a: MatRef<A>,
+                b: MatRef<B>,
+                beta: $scalar,
+                c: &mut MatMut<$scalar>,
+                pack_sizes: PackSizes,
+                packing_buf: &mut [$scalar],
+            ) where
+                A: Copy + Into<$scalar>,
+                B: Copy + Into<$scalar>,
+            {
+                dispatch!(self, [$($(#[$cfg])* $backend => $kernel,)+], |kernel| {
+                    kernel.gemm_mixed(alpha, a, b, beta, c, pack_sizes, packing_buf)
+                })
+            }
+            /// [`Kernel::gemm_mixed_into`](crate::Kernel::gemm_mixed_into)
+            /// with the selected microkernel.
+            #[allow(clippy::too_many_arguments)]
+            pub fn gemm_mixed_into<A, B, O>(
+                &self,
+                alpha: $scalar,
+                a: MatRef<A>,
+                b: MatRef<B>,
+                beta: O,
+                c: &mut MatMut<O>,
+                pack_sizes: PackSizes,
+                packing_buf: &mut [$scalar],
+            ) where
+                A: Copy + Into<$scalar>,
+                B: Copy + Into<$scalar>,
+                $scalar: Into<O>,
+                O: Copy + Zero + One,
+            {
+                dispatch!(self, [$($(#[$cfg])* $backend => $kernel,)+], |kernel| {
+                    kernel.gemm_mixed_into(alpha, a, b, beta, c, pack_sizes, packing_buf)
+                })
+            }
+            /// [`Kernel::gemm_with_executor`](crate::Kernel::gemm_with_executor)
+            /// with the selected microkernel.
+            #[allow(clippy::too_many_arguments)]
+            pub fn gemm_with_executor<E>(
+                &self,
+                executor: &E,
+                alpha: $scalar,
+                a: MatRef<$scalar>,
+                b: MatRef<$scalar>,
+                beta: $scalar,
+                c: &mut MatMut<$scalar>,
+                pack_sizes: PackSizes,
+                workspace: &mut [$scalar],
+            ) where
+                E: Executor + ?Sized,
+            {
+                dispatch!(self, [$($(#[$cfg])* $backend => $kernel,)+], |kernel| {
+                    kernel.gemm_with_executor(executor, alpha, a, b, beta, c, pack_sizes, workspace)
+                })
+            }
+            /// [`PackSizes::packed_a_len`] for the selected microkernel.
+            pub fn packed_a_len(&self, pack_sizes: PackSizes, m: usize, k: usize) -> usize {
+                dispatch!(self, [$($(#[$cfg])* $backend => $kernel,)+], |kernel| {
+                    pack_sizes.packed_a_len(&kernel, m, k)
+                })
+            }
+            /// [`PackSizes::packed_b_len`] for the selected microkernel.
+            pub fn packed_b_len(&self, pack_sizes: PackSizes, k: usize, n: usize) -> usize {
+                dispatch!(self, [$($(#[$cfg])* $backend => $kernel,)+], |kernel| {
+                    pack_sizes.packed_b_len(&kernel, k, n)
+                })
+            }
+            /// [`PackSizes::batched_buf_len`] for the selected microkernel.
+            pub fn batched_buf_len(&self, pack_sizes: PackSizes, k: usize, n: usize) -> usize {
+                dispatch!(self, [$($(#[$cfg])* $backend => $kernel,)+], |kernel| {
+                    pack_sizes.batched_buf_len(&kernel, k, n)
+                })
+            }
+            /// [`PackedA::pack_into`] for the selected microkernel.
+            ///
+            /// # Panics
+            /// If `buf.as_mut().len() != self.packed_a_len(pack_sizes, a.nrows(), a.ncols())`
+            /// or `pack_sizes` is too small for the selected microkernel.
+            pub fn pack_a_into<V>(
+                &self,
+                a: MatRef<$scalar>,
+                pack_sizes: PackSizes,
+                buf: V,
+            ) -> PackedA<V, $scalar>
+            where
+                V: AsMut<[$scalar]>,
+            {
+                dispatch!(self, [$($(#[$cfg])* $backend => $kernel,)+], |kernel| {
+                    PackedA::pack_into(&kernel, a, pack_sizes, buf)
+                })
+            }
+            /// [`PackedB::pack_into`] for the selected microkernel.
+            ///
+            /// # Panics
+            /// If `buf.as_mut().len() != self.packed_b_len(pack_sizes, b.nrows(), b.ncols())`
+            /// or `pack_sizes` is too small for the selected microkernel.
+            pub fn pack_b_into<V>(
+                &self,
+                b: MatRef<$scalar>,
+                pack_sizes: PackSizes,
+                buf: V,
+            ) -> PackedB<V, $scalar>
+            where
+                V: AsMut<[$scalar]>,
+            {
+                dispatch!(self, [$($(#[$cfg])* $backend => $kernel,)+], |kernel| {
+                    PackedB::pack_into(&kernel, b, pack_sizes, buf)
+                })
+            }
+            /// [`Kernel::gemm_prepacked_a`](crate::Kernel::gemm_prepacked_a)
+            /// with the selected microkernel, for `a` packed by [`pack_a_into`](Self::pack_a_into).
+            pub fn gemm_prepacked_a<V>(
+                &self,
+                alpha: $scalar,
+                a: &PackedA<V, $scalar>,
+                b: MatRef<$scalar>,
+                beta: $scalar,
+                c: &mut MatMut<$scalar>,
+                packing_buf: &mut [$scalar],
+            ) where
+                V: AsRef<[$scalar]>,
+            {
+                dispatch!(self, [$($(#[$cfg])* $backend => $kernel,)+], |kernel| {
+                    kernel.gemm_prepacked_a(alpha, a, b, beta, c, packing_buf)
+                })
+            }
+            /// [`Kernel::gemm_prepacked_b`](crate::Kernel::gemm_prepacked_b)
+            /// with the selected microkernel, for `b` packed by [`pack_b_into`](Self::pack_b_into).
+            pub fn gemm_prepacked_b<V>(
+                &self,
+                alpha: $scalar,
+                a: MatRef<$scalar>,
+                b: &PackedB<V, $scalar>,
+                beta: $scalar,
+                c: &mut MatMut<$scalar>,
+                packing_buf: &mut [$scalar],
+            ) where
+                V: AsRef<[$scalar]>,
+            {
+                dispatch!(self, [$($(#[$cfg])* $backend => $kernel,)+], |kernel| {
+                    kernel.gemm_prepacked_b(alpha, a, b, beta, c, packing_buf)
+                })
+            }
+            /// [`Kernel::par_gemm`](crate::Kernel::par_gemm) with the selected microkernel.
+            #[cfg(feature = "rayon")]
+            pub fn par_gemm(
+                &self,
+                alpha: $scalar,
+                a: MatRef<$scalar>,
+                b: MatRef<$scalar>,
+                beta: $scalar,
+                c: &mut MatMut<$scalar>,
+                pack_sizes: PackSizes,
+            ) {
+                dispatch!(self, [$($(#[$cfg])* $backend => $kernel,)+], |kernel| {
+                    kernel.par_gemm(alpha, a, b, beta, c, pack_sizes)
+                })
+            }
+        }
+
+        impl Default for AutoKernel<$scalar> {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+    };
+}
+
+impl_auto_kernel!(f32 {
+    #[cfg(target_arch = "aarch64")]
+    Neon => NeonKernel8x8::<f32>::new(),
+    #[cfg(target_arch = "x86_64")]
+    Avx2Fma => Avx2FmaKernel8x8::<f32>::new(),
+    #[cfg(target_arch = "x86_64")]
+    Sse2 => Sse2Kernel4x4::<f32>::new(),
+    Generic => GenericKernel8x8::<f32>::new(),
+});
+
+impl_auto_kernel!(f64 {
+    #[cfg(target_arch = "x86_64")]
+    Avx2Fma => Avx2FmaKernel8x4::<f64>::new(),
+    #[cfg(target_arch = "x86_64")]
+    Sse2 => Sse2Kernel4x2::<f64>::new(),
+    Generic => GenericKernel4x4::<f64>::new(),
+});
+
+#[cfg(not(miri))]
+#[cfg(test)]
+mod proptests {
+    use super::*;
+    use crate::{
+        as_mut,
+        std_prelude::*,
+        utils::{arb_matrix_triple_with, assert_approx_eq, naive_gemm},
+    };
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn proptest_auto_kernel_f32(
+            [a, b, c] in arb_matrix_triple_with(1..40, 1..40, 1..40, -1f32..1.0),
+            alpha in -1f32..1.0,
+            beta in -1f32..1.0,
+            [mc, kc, nc] in [1..4usize, 1..40, 1..4],
+        ) {
+            let kernel = AutoKernel::<f32>::new();
+            let pack_sizes = PackSizes { mc: mc * kernel.mr(), kc, nc: nc * kernel.nr() };
+            let mut buf = vec![0.0; pack_sizes.buf_len()];
+
+            let mut expect = c.clone();
+            let mut actual = c;
+            naive_gemm(alpha, a.to_ref(), b.to_ref(), beta, as_mut!(expect));
+            kernel.gemm(alpha, a.to_ref(), b.to_ref(), beta, as_mut!(actual), pack_sizes, &mut buf);
+            assert_approx_eq(expect.as_slice(), actual.as_slice(), 75.0 * f32::EPSILON);
+        }
+
+        #[test]
+        fn proptest_auto_kernel_f64(
+            [a, b, c] in arb_matrix_triple_with(1..40, 1..40, 1..40, -1f64..1.0),
+            alpha in -1f64..1.0,
+            beta in -1f64..1.0,
+            [mc, kc, nc] in [1..4usize, 1..40, 1..4],
+        ) {
+            let kernel = AutoKernel::<f64>::new();
+            let pack_sizes = PackSizes { mc: mc * kernel.mr(), kc, nc: nc * kernel.nr() };
+            let mut buf = vec![0.0; pack_sizes.buf_len()];
+
+            let mut expect = c.clone();
+            let mut actual = c;
+            naive_gemm(alpha, a.to_ref(), b.to_ref(), beta, as_mut!(expect));
+            kernel.gemm(alpha, a.to_ref(), b.to_ref(), beta, as_mut!(actual), pack_sizes, &mut buf);
+            assert_approx_eq(expect.as_slice(), actual.as_slice(), 75.0 * f64::EPSILON);
+        }
+    }
+
+    #[test]
+    fn test_auto_kernel_beta_zero_overwrites_nan() {
+        let [m, k, n] = [19, 23, 17];
+        let a = (0..m * k).map(|x| (x % 7) as f64).collect::<Vec<_>>();
+        let b = (0..k * n).map(|x| (x % 5) as f64 - 2.0).collect::<Vec<_>>();
+        let a = MatRef::row_major(m, k, &a);
+        let b = MatRef::row_major(k, n, &b);
+
+        let mut expect = vec![0.0; m * n];
+        naive_gemm(1.0, a, b, 0.0, &mut MatMut::col_major(m, n, &mut expect));
+
+        let kernel = AutoKernel::<f64>::new();
+        let pack_sizes = PackSizes {
+            mc: kernel.mr(),
+            kc: 8,
+            nc: kernel.nr(),
+        };
+        let mut buf = vec![0.0; pack_sizes.buf_len()];
+        let mut c = vec![f64::NAN; m * n];
+        kernel.gemm(
+            1.0,
+            a,
+            b,
+            0.0,
+            &mut MatMut::col_major(m, n, &mut c),
+            pack_sizes,
+            &mut buf,
+        );
+        assert_eq!(c, expect);
+
+        let a = a.as_slice().iter().map(|&x| x as f32).collect::<Vec<_>>();
+        let b = b.as_slice().iter().map(|&x| x as f32).collect::<Vec<_>>();
+        let expect = expect.iter().map(|&x| x as f32).collect::<Vec<_>>();
+        let a = MatRef::row_major(m, k, &a);
+        let b = MatRef::row_major(k, n, &b);
+
+        let kernel = AutoKernel::<f32>::new();
+        let pack_sizes = PackSizes {
+            mc: kernel.mr(),
+            kc: 8,
+            nc: kernel.nr(),
+        };
+        let mut buf = vec![0.0; pack_sizes.buf_len()];
+        let mut c = vec![f32::INFINITY; m * n];
+        kernel.gemm(
+            1.0,
+            a,
+            b,
+            0.0,
+            &mut MatMut::col_major(m, n, &mut c),
+            pack_sizes,
+            &mut buf,
+        );
+        assert_eq!(c, expect);
+    }
+
+    #[test]
+    fn test_auto_kernel_pack_sizes() {
+        let kernel = AutoKernel::<f32>::new();
+        let pack_sizes = kernel.pack_sizes();
+        assert!(kernel.mr() <= pack_sizes.mc);
+        assert!(kernel.nr() <= pack_sizes.nc);
+
+        let kernel = AutoKernel::<f64>::new();
+        let pack_sizes = kernel.pack_sizes();
+        assert!(kernel.mr() <= pack_sizes.mc);
+        assert!(kernel.nr() <= pack_sizes.nc);
+        assert_eq!(pack_sizes.mc % kernel.mr(), 0);
+        assert_eq!(pack_sizes.nc % kernel.nr(), 0);
+
+        let fallback = PackSizes::for_kernel::<GenericKernel8x8<f32>>(FALLBACK_CACHE);
+        assert_eq!(
+            fallback,
+            PackSizes {
+                mc: 128,
+                kc: 256,
+                nc: 4096
+            }
+        );
+    }
+
+    #[test]
+    fn test_auto_kernel_drivers() {
+        let [m, k, n] = [13, 7, 11];
+        let a = (0..m * k).map(|x| (x % 5) as f32).collect::<Vec<_>>();
+        let b = (0..k * n).map(|x| (x % 3) as f32 - 1.0).collect::<Vec<_>>();
+        let a = MatRef::row_major(m, k, &a);
+        let b = MatRef::row_major(k, n, &b);
+        let mut expect = vec![0.0; m * n];
+        naive_gemm(1.0, a, b, 0.0, &mut MatMut::row_major(m, n, &mut expect));
+
+        let kernel = AutoKernel::<f32>::new();
+        let pack_sizes = kernel.pack_sizes();
+        let mut buf = vec![0.0; pack_sizes.buf_len()];
+
+        let mut c = vec![0.0; m * n];
+        let relu = |_, _, x: f32| x.max(0.0);
+        let mut c_mat = MatMut::row_major(m, n, &mut c);
+        kernel.gemm_with_epilogue(1.0, a, b, 0.0, &mut c_mat, pack_sizes, &mut buf, relu);
+        let relu_expect = expect.iter().map(|x| x.max(0.0)).collect::<Vec<_>>();
+        assert_eq!(c, relu_expect);
+
+        let mut c = vec![0.0; 2 * m * n];
+        let (c0, c1) = c.split_at_mut(m * n);
+        let mut c = [MatMut::row_major(m, n, c0), MatMut::row_major(m, n, c1)];
+        kernel.gemm_batched(1.0, &[a, a], &[b, b], 0.0, &mut c, pack_sizes, &mut buf);
+        assert_eq!(c[0].as_slice(), expect);
+        assert_eq!(c[1].as_slice(), expect);
+
+        let mut c = vec![0.0; 2 * m * n];
+        kernel.gemm_strided_batched(
+            2,
+            1.0,
+            a,
+            0,
+            b,
+            0,
+            0.0,
+            &mut MatMut::from_parts(m, n, &mut c[..], n, 1).unwrap(),
+            m * n,
+            pack_sizes,
+            &mut buf,
+        );
+        assert_eq!(c[..m * n], expect);
+        assert_eq!(c[m * n..], expect);
+
+        let a16 = a.as_slice().iter().map(|&x| x as i16).collect::<Vec<_>>();
+        let a16 = MatRef::row_major(m, k, &a16);
+        let mut c = vec![0.0; m * n];
+        let mut c_mat = MatMut::row_major(m, n, &mut c);
+        kernel.gemm_mixed(1.0, a16, b, 0.0, &mut c_mat, pack_sizes, &mut buf);
+        assert_eq!(c, expect);
+
+        let mut c = vec![0f64; m * n];
+        let mut c_mat = MatMut::row_major(m, n, &mut c);
+        kernel.gemm_mixed_into(1.0, a16, b, 0.0, &mut c_mat, pack_sizes, &mut buf);
+        assert!(c.iter().zip(&expect).all(|(&x, &y)| x == y.into()));
+
+        let mut packed = vec![0.0; kernel.packed_a_len(pack_sizes, m, k)];
+        let packed_a = kernel.pack_a_into(a, pack_sizes, &mut packed[..]);
+        let mut c = vec![0.0; m * n];
+        let mut c_mat = MatMut::row_major(m, n, &mut c);
+        let bpack = &mut buf[..pack_sizes.kc * pack_sizes.nc];
+        kernel.gemm_prepacked_a(1.0, &packed_a, b, 0.0, &mut c_mat, bpack);
+        assert_eq!(c, expect);
+
+        let mut packed = vec![0.0; kernel.packed_b_len(pack_sizes, k, n)];
+        let packed_b = kernel.pack_b_into(b, pack_sizes, &mut packed[..]);
+        let mut c = vec![0.0; m * n];
+        let mut c_mat = MatMut::row_major(m, n, &mut c);
+        let apack = &mut buf[..pack_sizes.mc * pack_sizes.kc];
+        kernel.gemm_prepacked_b(1.0, a, &packed_b, 0.0, &mut c_mat, apack);
+        assert_eq!(c, expect);
+
+        struct Sequential;
+
+        impl Executor for Sequential {
+            fn workers(&self) -> usize {
+                2
+            }
+
+            fn run(&self, task: &(dyn Fn() + Sync)) {
+                task();
+                task();
+            }
+        }
+
+        let mut workspace = vec![0.0; pack_sizes.par_buf_len(2)];
+        let mut c = vec![0.0; m * n];
+        let mut c_mat = MatMut::row_major(m, n, &mut c);
+        kernel.gemm_with_executor(
+            &Sequential,
+            1.0,
+            a,
+            b,
+            0.0,
+            &mut c_mat,
+            pack_sizes,
+            &mut workspace,
+        );
+        assert_eq!(c, expect);
+
+        #[cfg(feature = "rayon")]
+        {
+            let mut c = vec![0.0; m * n];
+            kernel.par_gemm(
+                1.0,
+                a,
+                b,
+                0.0,
+                &mut MatMut::row_major(m, n, &mut c),
+                pack_sizes,
+            );
+            assert_eq!(c, expect);
+        }
+    }
+}
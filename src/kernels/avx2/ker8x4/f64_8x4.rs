@@ -0,0 +1,150 @@
+use super::Avx2FmaKernel8x4;
+use crate::{
+    kernels::dbg_check_microkernel_inputs,
+    typenum::{U4, U8},
+    Kernel, MatMut, MatRef,
+};
+
+use super::super::simd::*;
+
+impl Kernel for Avx2FmaKernel8x4<f64> {
+    type Scalar = f64;
+    type Mr = U8;
+    type Nr = U4;
+
+    const STRIDED_DST: bool = true;
+
+    fn microkernel(
+        &self,
+        alpha: f64,
+        lhs: MatRef<f64>,
+        rhs: MatRef<f64>,
+        beta: f64,
+        dst: &mut MatMut<f64>,
+    ) {
+        dbg_check_microkernel_inputs(self, lhs, rhs, dst);
+        let kc = lhs.ncols();
+        let col_stride = dst.col_stride();
+        avx2_fma_8x4_microkernel_f64(
+            kc,
+            alpha,
+            lhs.as_slice(),
+            rhs.as_slice(),
+            beta,
+            dst.as_mut_slice(),
+            col_stride,
+        );
+    }
+}
+
+fn avx2_fma_8x4_microkernel_f64(
+    kc: usize,
+    alpha: f64,
+    lhs: &[f64],
+    rhs: &[f64],
+    beta: f64,
+    dst: &mut [f64],
+    col_stride: usize,
+) {
+    const MR: usize = 8;
+    const NR: usize = 4;
+    assert_eq!(lhs.len(), MR.checked_mul(kc).unwrap());
+    assert_eq!(rhs.len(), NR.checked_mul(kc).unwrap());
+    assert!(col_stride >= MR);
+    assert_eq!(dst.len(), (NR - 1) * col_stride + MR);
+
+    unsafe {
+        inner(
+            kc,
+            alpha,
+            lhs.as_ptr(),
+            rhs.as_ptr(),
+            beta,
+            dst.as_mut_ptr(),
+            col_stride,
+        )
+    };
+
+    #[cfg_attr(not(any(kani, miri)), target_feature(enable = "avx2,fma"))]
+    unsafe fn inner(
+        kc: usize,
+        alpha: f64,
+        mut a: *const f64,
+        mut b: *const f64,
+        beta: f64,
+        c: *mut f64,
+        col_stride: usize,
+    ) {
+        // upper and lower halves of the 4 columns
+        let mut cols1 = [_mm256_setzero_pd(); 4];
+        let mut cols2 = [_mm256_setzero_pd(); 4];
+
+        // Compute
+        // col_j += a * b_j for all j
+        for _ in 0..kc {
+            let a1 = _mm256_loadu_pd(a);
+            let a2 = _mm256_loadu_pd(a.add(4));
+            for j in 0..4 {
+                let bv = _mm256_set1_pd(*b.add(j));
+                cols1[j] = _mm256_fmadd_pd(a1, bv, cols1[j]);
+                cols2[j] = _mm256_fmadd_pd(a2, bv, cols2[j]);
+            }
+            a = a.add(8);
+            b = b.add(4);
+        }
+
+        let alphav = _mm256_set1_pd(alpha);
+        let betav = _mm256_set1_pd(beta);
+        for j in 0..4 {
+            let to1 = c.add(col_stride * j);
+            let to2 = to1.add(4);
+            let ab1 = _mm256_mul_pd(cols1[j], alphav);
+            let ab2 = _mm256_mul_pd(cols2[j], alphav);
+            _mm256_storeu_pd(to1, _mm256_fmadd_pd(_mm256_loadu_pd(to1), betav, ab1));
+            _mm256_storeu_pd(to2, _mm256_fmadd_pd(_mm256_loadu_pd(to2), betav, ab2));
+        }
+    }
+}
+
+#[cfg(kani)]
+mod proofs {
+    use super::*;
+
+    const MR: usize = 8;
+    const NR: usize = 4;
+
+    const fn max(a: usize, b: usize) -> usize {
+        if a < b {
+            b
+        } else {
+            a
+        }
+    }
+
+    #[kani::proof]
+    #[kani::unwind(5)] // 1 + max(4, kc)
+    fn check_avx2_fma_8x4_microkernel_f64() -> Option<()> {
+        const KC_LIMIT: usize = 2;
+        const MAX_VEC_LEN: usize = 3 + max(MR * KC_LIMIT, NR * (MR + 1));
+
+        let kc: usize = kani::any_where(|&kc| kc <= KC_LIMIT);
+        let alpha: f64 = kani::any();
+        let beta: f64 = kani::any();
+
+        let left = kani::vec::any_vec::<f64, MAX_VEC_LEN>();
+        let right = kani::vec::any_vec::<f64, MAX_VEC_LEN>();
+        kani::assume(left.len() >= MR * kc);
+        kani::assume(right.len() >= NR * kc);
+        let left = &left[..MR * kc];
+        let right = &right[..NR * kc];
+
+        let col_stride: usize = kani::any_where(|&cs| (MR..=MR + 1).contains(&cs));
+        let dst_len = (NR - 1) * col_stride + MR;
+        let mut dst = kani::vec::any_vec::<f64, MAX_VEC_LEN>();
+        kani::assume(dst.len() >= dst_len);
+        let dst = &mut dst[..dst_len];
+
+        avx2_fma_8x4_microkernel_f64(kc, alpha, left, right, beta, dst, col_stride);
+        Some(())
+    }
+}
@@ -0,0 +1,72 @@
+#[cfg(target_arch = "x86_64")]
+mod f64_8x4;
+
+use core::marker::PhantomData;
+
+/// Available only for the `x86_64` target.
+#[derive(Debug, Clone, Copy)]
+pub struct Avx2FmaKernel8x4<T> {
+    marker: PhantomData<T>,
+}
+
+impl<T> Avx2FmaKernel8x4<T> {
+    /// # Safety
+    ///
+    /// The caller must ensure that the created kernel will only be used in an
+    /// environment with `avx2` and `fma` support.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use microgemm::kernels::Avx2FmaKernel8x4;
+    ///
+    /// let kernel = if cfg!(all(target_feature = "avx2", target_feature = "fma")) {
+    ///     unsafe { Avx2FmaKernel8x4::<f64>::new() }
+    /// } else {
+    ///     panic!("avx2 and fma target features are not enabled");
+    /// };
+    /// ```
+    #[cfg(not(doctest))]
+    pub const unsafe fn new() -> Self {
+        Self {
+            marker: PhantomData,
+        }
+    }
+    /// Creates the kernel if `avx2` and `fma` are supported.
+    ///
+    /// With the `std` feature the support is detected at runtime,
+    /// otherwise only the compile-time target features are checked.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use microgemm::kernels::Avx2FmaKernel8x4;
+    ///
+    /// match Avx2FmaKernel8x4::<f64>::try_new() {
+    ///     Some(kernel) => println!("{kernel:?}"),
+    ///     None => println!("avx2 and fma are not supported"),
+    /// }
+    /// ```
+    pub fn try_new() -> Option<Self> {
+        if super::is_avx2_fma_available() {
+            Some(Self {
+                marker: PhantomData,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    fn _check_new_avx2_fma() {
+        use crate::kernels::Avx2FmaKernel8x4;
+
+        let _kernel = if cfg!(all(target_feature = "avx2", target_feature = "fma")) {
+            unsafe { Avx2FmaKernel8x4::<f64>::new() }
+        } else {
+            panic!("avx2 and fma target features are not enabled");
+        };
+    }
+}
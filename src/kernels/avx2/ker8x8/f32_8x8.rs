@@ -0,0 +1,138 @@
+use super::Avx2FmaKernel8x8;
+use crate::{kernels::dbg_check_microkernel_inputs, typenum::U8, Kernel, MatMut, MatRef};
+
+use super::super::simd::*;
+
+impl Kernel for Avx2FmaKernel8x8<f32> {
+    type Scalar = f32;
+    type Mr = U8;
+    type Nr = U8;
+
+    const STRIDED_DST: bool = true;
+
+    fn microkernel(
+        &self,
+        alpha: f32,
+        lhs: MatRef<f32>,
+        rhs: MatRef<f32>,
+        beta: f32,
+        dst: &mut MatMut<f32>,
+    ) {
+        dbg_check_microkernel_inputs(self, lhs, rhs, dst);
+        let kc = lhs.ncols();
+        let col_stride = dst.col_stride();
+        avx2_fma_8x8_microkernel_f32(
+            kc,
+            alpha,
+            lhs.as_slice(),
+            rhs.as_slice(),
+            beta,
+            dst.as_mut_slice(),
+            col_stride,
+        );
+    }
+}
+
+fn avx2_fma_8x8_microkernel_f32(
+    kc: usize,
+    alpha: f32,
+    lhs: &[f32],
+    rhs: &[f32],
+    beta: f32,
+    dst: &mut [f32],
+    col_stride: usize,
+) {
+    const DIM: usize = 8;
+    assert_eq!(lhs.len(), rhs.len());
+    assert_eq!(lhs.len(), DIM.checked_mul(kc).unwrap());
+    assert!(col_stride >= DIM);
+    assert_eq!(dst.len(), (DIM - 1) * col_stride + DIM);
+
+    unsafe {
+        inner(
+            kc,
+            alpha,
+            lhs.as_ptr(),
+            rhs.as_ptr(),
+            beta,
+            dst.as_mut_ptr(),
+            col_stride,
+        )
+    };
+
+    #[cfg_attr(not(any(kani, miri)), target_feature(enable = "avx2,fma"))]
+    unsafe fn inner(
+        kc: usize,
+        alpha: f32,
+        mut a: *const f32,
+        mut b: *const f32,
+        beta: f32,
+        c: *mut f32,
+        col_stride: usize,
+    ) {
+        let mut cols = [_mm256_setzero_ps(); 8];
+
+        // Compute
+        // col_j += a * b_j for all j
+        for _ in 0..kc {
+            let av = _mm256_loadu_ps(a);
+            for (j, col) in cols.iter_mut().enumerate() {
+                let bv = _mm256_set1_ps(*b.add(j));
+                *col = _mm256_fmadd_ps(av, bv, *col);
+            }
+            a = a.add(8);
+            b = b.add(8);
+        }
+
+        let alphav = _mm256_set1_ps(alpha);
+        let betav = _mm256_set1_ps(beta);
+        for (j, col) in cols.into_iter().enumerate() {
+            let to = c.add(col_stride * j);
+            let ab = _mm256_mul_ps(col, alphav);
+            let out = _mm256_fmadd_ps(_mm256_loadu_ps(to), betav, ab);
+            _mm256_storeu_ps(to, out);
+        }
+    }
+}
+
+#[cfg(kani)]
+mod proofs {
+    use super::*;
+
+    const DIM: usize = 8;
+
+    const fn max(a: usize, b: usize) -> usize {
+        if a < b {
+            b
+        } else {
+            a
+        }
+    }
+
+    #[kani::proof]
+    #[kani::unwind(9)] // 1 + max(8, kc)
+    fn check_avx2_fma_8x8_microkernel_f32() -> Option<()> {
+        const KC_LIMIT: usize = 2;
+        const MAX_VEC_LEN: usize = 3 + max(DIM * KC_LIMIT, DIM * (DIM + 1));
+
+        let kc: usize = kani::any_where(|&kc| kc <= KC_LIMIT);
+        let alpha: f32 = kani::any();
+        let beta: f32 = kani::any();
+
+        let left = kani::vec::any_vec::<f32, MAX_VEC_LEN>();
+        let right = kani::vec::any_vec::<f32, MAX_VEC_LEN>();
+        kani::assume(left.len() >= DIM * kc);
+        kani::assume(right.len() >= DIM * kc);
+        let left = &left[..DIM * kc];
+        let right = &right[..DIM * kc];
+
+        let col_stride: usize = kani::any_where(|&cs| (DIM..=DIM + 1).contains(&cs));
+        let dst_len = (DIM - 1) * col_stride + DIM;
+        let mut dst = kani::vec::any_vec::<f32, MAX_VEC_LEN>();
+        kani::assume(dst.len() >= dst_len);
+        let dst = &mut dst[..dst_len];
+
+        avx2_fma_8x8_microkernel_f32(kc, alpha, left, right, beta, dst, col_stride);
+        Some(())
+    }
+}
@@ -0,0 +1,70 @@
+mod ker8x4;
+mod ker8x8;
+
+#[cfg(any(kani, miri))]
+mod simd_mock;
+
+#[cfg(target_arch = "x86_64")]
+mod simd {
+    #[cfg(any(kani, miri))]
+    pub use super::simd_mock::{
+        _mm256_fmadd_pd, _mm256_fmadd_ps, _mm256_loadu_pd, _mm256_loadu_ps, _mm256_mul_pd,
+        _mm256_mul_ps, _mm256_set1_pd, _mm256_set1_ps, _mm256_setzero_pd, _mm256_setzero_ps,
+        _mm256_storeu_pd, _mm256_storeu_ps,
+    };
+    #[cfg(not(any(kani, miri)))]
+    pub use core::arch::x86_64::{
+        _mm256_fmadd_pd, _mm256_fmadd_ps, _mm256_loadu_pd, _mm256_loadu_ps, _mm256_mul_pd,
+        _mm256_mul_ps, _mm256_set1_pd, _mm256_set1_ps, _mm256_setzero_pd, _mm256_setzero_ps,
+        _mm256_storeu_pd, _mm256_storeu_ps,
+    };
+}
+
+pub use ker8x4::Avx2FmaKernel8x4;
+pub use ker8x8::Avx2FmaKernel8x8;
+
+/// Checks `avx2` and `fma` support at runtime if the `std` feature is enabled,
+/// otherwise falls back to the compile-time target features.
+#[allow(dead_code)]
+pub(crate) fn is_avx2_fma_available() -> bool {
+    #[cfg(all(feature = "std", target_arch = "x86_64"))]
+    {
+        std::is_x86_feature_detected!("avx2") && std::is_x86_feature_detected!("fma")
+    }
+    #[cfg(not(all(feature = "std", target_arch = "x86_64")))]
+    {
+        cfg!(all(target_feature = "avx2", target_feature = "fma"))
+    }
+}
+
+#[cfg(not(miri))]
+#[cfg(test)]
+mod proptests {
+    use super::*;
+    use crate::{
+        testing::proptest_kernel,
+        utils::{simd_cfg, skip_test},
+    };
+    use std::arch::is_x86_feature_detected;
+
+    fn is_avx2_fma_detected() -> bool {
+        is_x86_feature_detected!("avx2") && is_x86_feature_detected!("fma")
+    }
+
+    #[test]
+    fn proptest_avx2_fma_kernel_8x8_f32() {
+        if !is_avx2_fma_detected() {
+            return skip_test("avx2 and fma are not supported");
+        }
+        let kernel = unsafe { Avx2FmaKernel8x8::new() };
+        proptest_kernel(&kernel, simd_cfg::<f32>()).unwrap();
+    }
+    #[test]
+    fn proptest_avx2_fma_kernel_8x4_f64() {
+        if !is_avx2_fma_detected() {
+            return skip_test("avx2 and fma are not supported");
+        }
+        let kernel = unsafe { Avx2FmaKernel8x4::new() };
+        proptest_kernel(&kernel, simd_cfg::<f64>()).unwrap();
+    }
+}
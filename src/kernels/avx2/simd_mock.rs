@@ -0,0 +1,127 @@
+const F32_BOUND: f32 = 1e3;
+
+fn add_f32(a: f32, b: f32) -> f32 {
+    #[cfg(kani)]
+    {
+        kani::assume(a.abs() < F32_BOUND);
+        kani::assume(b.abs() < F32_BOUND);
+    }
+    a + b
+}
+
+fn mul_f32(a: f32, b: f32) -> f32 {
+    #[cfg(kani)]
+    {
+        kani::assume(a.abs() < F32_BOUND);
+        kani::assume(b.abs() < F32_BOUND);
+    }
+    a * b
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy)]
+pub struct __m256([f32; 8]);
+
+pub unsafe fn _mm256_setzero_ps() -> __m256 {
+    _mm256_set1_ps(0f32)
+}
+
+pub unsafe fn _mm256_set1_ps(value: f32) -> __m256 {
+    __m256([value; 8])
+}
+
+pub unsafe fn _mm256_loadu_ps(ptr: *const f32) -> __m256 {
+    let mut out = _mm256_setzero_ps();
+    for i in 0..8 {
+        out.0[i] = *ptr.add(i);
+    }
+    out
+}
+
+pub unsafe fn _mm256_storeu_ps(ptr: *mut f32, a: __m256) {
+    for i in 0..8 {
+        *ptr.add(i) = a.0[i];
+    }
+}
+
+pub unsafe fn _mm256_mul_ps(__m256(a): __m256, __m256(b): __m256) -> __m256 {
+    let mut out = _mm256_setzero_ps();
+    for i in 0..8 {
+        out.0[i] = mul_f32(a[i], b[i]);
+    }
+    out
+}
+
+pub unsafe fn _mm256_fmadd_ps(__m256(a): __m256, __m256(b): __m256, __m256(c): __m256) -> __m256 {
+    let mut out = _mm256_setzero_ps();
+    for i in 0..8 {
+        out.0[i] = add_f32(mul_f32(a[i], b[i]), c[i]);
+    }
+    out
+}
+
+const F64_BOUND: f64 = 1e3;
+
+fn add_f64(a: f64, b: f64) -> f64 {
+    #[cfg(kani)]
+    {
+        kani::assume(a.abs() < F64_BOUND);
+        kani::assume(b.abs() < F64_BOUND);
+    }
+    a + b
+}
+
+fn mul_f64(a: f64, b: f64) -> f64 {
+    #[cfg(kani)]
+    {
+        kani::assume(a.abs() < F64_BOUND);
+        kani::assume(b.abs() < F64_BOUND);
+    }
+    a * b
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy)]
+pub struct __m256d([f64; 4]);
+
+pub unsafe fn _mm256_setzero_pd() -> __m256d {
+    _mm256_set1_pd(0f64)
+}
+
+pub unsafe fn _mm256_set1_pd(value: f64) -> __m256d {
+    __m256d([value; 4])
+}
+
+pub unsafe fn _mm256_loadu_pd(ptr: *const f64) -> __m256d {
+    let mut out = _mm256_setzero_pd();
+    for i in 0..4 {
+        out.0[i] = *ptr.add(i);
+    }
+    out
+}
+
+pub unsafe fn _mm256_storeu_pd(ptr: *mut f64, a: __m256d) {
+    for i in 0..4 {
+        *ptr.add(i) = a.0[i];
+    }
+}
+
+pub unsafe fn _mm256_mul_pd(__m256d(a): __m256d, __m256d(b): __m256d) -> __m256d {
+    let mut out = _mm256_setzero_pd();
+    for i in 0..4 {
+        out.0[i] = mul_f64(a[i], b[i]);
+    }
+    out
+}
+
+pub unsafe fn _mm256_fmadd_pd(
+    __m256d(a): __m256d,
+    __m256d(b): __m256d,
+    __m256d(c): __m256d,
+) -> __m256d {
+    let mut out = _mm256_setzero_pd();
+    for i in 0..4 {
+        out.0[i] = add_f64(mul_f64(a[i], b[i]), c[i]);
+    }
+    out
+}
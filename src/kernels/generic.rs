@@ -1,7 +1,7 @@
 use crate::{
     kernels::dbg_check_microkernel_inputs,
     typenum::{U16, U2, U32, U4, U8},
-    Kernel, One, Zero,
+    Kernel, MatMut, One, Zero,
 };
 use core::marker::PhantomData;
 use core::ops::{Add, Mul};
@@ -29,15 +29,27 @@ where
     });
 }
 
-fn write_cols_to_colmajor<T, const DIM: usize>(dst: &mut [T], cols: &[T], alpha: T, beta: T)
-where
+fn write_cols_to_colmajor<T, const DIM: usize>(
+    dst: &mut MatMut<T>,
+    cols: &[T],
+    alpha: T,
+    beta: Option<T>,
+) where
     T: Copy + Add<Output = T> + Mul<Output = T>,
 {
-    assert_eq!(dst.len(), DIM * DIM);
-    assert_eq!(cols.len(), dst.len());
-    dst.iter_mut().zip(cols).for_each(|(to, &from)| {
-        *to = alpha * from + beta * *to;
-    });
+    assert_eq!(dst.row_stride(), 1);
+    assert_eq!(cols.len(), DIM * DIM);
+    let col_stride = dst.col_stride();
+    let dst = dst.as_mut_slice();
+    for (j, from) in cols.chunks_exact(DIM).enumerate() {
+        let to = &mut dst[j * col_stride..j * col_stride + DIM];
+        to.iter_mut().zip(from).for_each(|(to, &from)| {
+            *to = match beta {
+                Some(beta) => alpha * from + beta * *to,
+                None => alpha * from,
+            };
+        });
+    }
 }
 
 macro_rules! impl_generic_square_kernel {
@@ -58,6 +70,8 @@ macro_rules! impl_generic_square_kernel {
             type Mr = $dimty;
             type Nr = $dimty;
 
+            const STRIDED_DST: bool = true;
+
             fn microkernel(
                 &self,
                 alpha: Self::Scalar,
@@ -71,7 +85,22 @@ macro_rules! impl_generic_square_kernel {
                 const DIM: usize = $dim;
                 let mut cols = [T::zero(); DIM * DIM];
                 loop_micropanels::<_, DIM>(lhs.as_slice(), rhs.as_slice(), &mut cols);
-                write_cols_to_colmajor::<_, DIM>(dst.as_mut_slice(), &cols, alpha, beta);
+                write_cols_to_colmajor::<_, DIM>(dst, &cols, alpha, Some(beta));
+            }
+
+            fn microkernel_overwrite(
+                &self,
+                alpha: Self::Scalar,
+                lhs: crate::MatRef<Self::Scalar>,
+                rhs: crate::MatRef<Self::Scalar>,
+                dst: &mut crate::MatMut<Self::Scalar>,
+            ) {
+                dbg_check_microkernel_inputs(self, lhs, rhs, dst);
+
+                const DIM: usize = $dim;
+                let mut cols = [T::zero(); DIM * DIM];
+                loop_micropanels::<_, DIM>(lhs.as_slice(), rhs.as_slice(), &mut cols);
+                write_cols_to_colmajor::<_, DIM>(dst, &cols, alpha, None);
             }
         }
     };
@@ -89,7 +118,8 @@ mod proptests {
     use super::*;
     use crate::{
         std_prelude::*,
-        utils::{is_debug_build, proptest_kernel, ProptestKernelCfg},
+        testing::{proptest_kernel, ProptestKernelCfg},
+        utils::is_debug_build,
     };
     use proptest::prelude::*;
 
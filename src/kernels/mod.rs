@@ -1,14 +1,24 @@
+mod auto;
+#[cfg(any(target_arch = "x86_64", doc))]
+mod avx2;
 mod generic;
 #[cfg(any(target_arch = "aarch64", doc))]
 mod neon;
+#[cfg(any(target_arch = "x86_64", doc))]
+mod sse2;
 
 use crate::{Kernel, MatMut, MatRef};
 
+pub use auto::AutoKernel;
+#[cfg(any(target_arch = "x86_64", doc))]
+pub use avx2::{Avx2FmaKernel8x4, Avx2FmaKernel8x8};
 pub use generic::{
     GenericKernel16x16, GenericKernel2x2, GenericKernel32x32, GenericKernel4x4, GenericKernel8x8,
 };
 #[cfg(any(target_arch = "aarch64", doc))]
 pub use neon::{NeonKernel4x4, NeonKernel8x8};
+#[cfg(any(target_arch = "x86_64", doc))]
+pub use sse2::{Sse2Kernel4x2, Sse2Kernel4x4};
 
 fn dbg_check_microkernel_inputs<T, K>(_: &K, lhs: MatRef<T>, rhs: MatRef<T>, dst: &mut MatMut<T>)
 where
@@ -8,6 +8,8 @@ impl Kernel for NeonKernel4x4<f32> {
     type Mr = U4;
     type Nr = U4;
 
+    const STRIDED_DST: bool = true;
+
     fn microkernel(
         &self,
         alpha: f32,
@@ -18,6 +20,7 @@ impl Kernel for NeonKernel4x4<f32> {
     ) {
         dbg_check_microkernel_inputs(self, lhs, rhs, dst);
         let kc = lhs.ncols();
+        let col_stride = dst.col_stride();
         neon_4x4_microkernel_f32(
             kc,
             alpha,
@@ -25,6 +28,7 @@ impl Kernel for NeonKernel4x4<f32> {
             rhs.as_slice(),
             beta,
             dst.as_mut_slice(),
+            col_stride,
         );
     }
 }
@@ -35,14 +39,16 @@ fn neon_4x4_microkernel_f32(
     lhs: &[f32],
     rhs: &[f32],
     beta: f32,
-    dst_colmajor: &mut [f32],
+    dst: &mut [f32],
+    col_stride: usize,
 ) {
     const DIM: usize = 4;
     assert_eq!(lhs.len(), rhs.len());
     assert_eq!(lhs.len(), DIM.checked_mul(kc).unwrap());
-    assert_eq!(dst_colmajor.len(), DIM * DIM);
+    assert!(col_stride >= DIM);
+    assert_eq!(dst.len(), (DIM - 1) * col_stride + DIM);
 
-    unsafe { inner(kc, alpha, lhs.as_ptr(), rhs.as_ptr(), beta, dst_colmajor) };
+    unsafe { inner(kc, alpha, lhs.as_ptr(), rhs.as_ptr(), beta, dst, col_stride) };
 
     unsafe fn inner(
         kc: usize,
@@ -51,6 +57,7 @@ fn neon_4x4_microkernel_f32(
         mut right: *const f32,
         beta: f32,
         dst: &mut [f32],
+        col_stride: usize,
     ) {
         let mut cols0 = [vmovq_n_f32(0f32); 4];
         let mut cols1 = [vmovq_n_f32(0f32); 4];
@@ -104,8 +111,8 @@ fn neon_4x4_microkernel_f32(
             cols0[row] = vmulq_n_f32(sum, alpha);
         }
 
-        let it = dst.chunks_exact_mut(4).zip(cols0);
-        for (to, from) in it {
+        for (j, from) in cols0.into_iter().enumerate() {
+            let to = &mut dst[j * col_stride..j * col_stride + 4];
             let mut tmp = [0f32; 4];
             vst1q_f32(tmp.as_mut_ptr(), from);
             for (y, x) in to.iter_mut().zip(tmp) {
@@ -140,7 +147,7 @@ mod proofs {
     #[kani::unwind(5)] // 1 + max(4, kc / 4)
     fn check_neon_4x4_microkernel_f32() -> Option<()> {
         const KC_LIMIT: usize = 8;
-        const MAX_VEC_LEN: usize = 3 + max(DIM * KC_LIMIT, DIM * DIM);
+        const MAX_VEC_LEN: usize = 3 + max(DIM * KC_LIMIT, DIM * (DIM + 1));
 
         let kc: usize = kani::any_where(|&kc| kc <= KC_LIMIT);
         let alpha: f32 = kani::any();
@@ -153,11 +160,13 @@ mod proofs {
         let left = &left[..DIM * kc];
         let right = &right[..DIM * kc];
 
+        let col_stride: usize = kani::any_where(|&cs| (DIM..=DIM + 1).contains(&cs));
+        let dst_len = (DIM - 1) * col_stride + DIM;
         let mut dst = kani::vec::any_vec::<f32, MAX_VEC_LEN>();
-        kani::assume(dst.len() >= DIM * DIM);
-        let dst = &mut dst[..DIM * DIM];
+        kani::assume(dst.len() >= dst_len);
+        let dst = &mut dst[..dst_len];
 
-        neon_4x4_microkernel_f32(kc, alpha, left, right, beta, dst);
+        neon_4x4_microkernel_f32(kc, alpha, left, right, beta, dst, col_stride);
         Some(())
     }
 }
@@ -8,6 +8,8 @@ impl Kernel for NeonKernel8x8<f32> {
     type Mr = U8;
     type Nr = U8;
 
+    const STRIDED_DST: bool = true;
+
     fn microkernel(
         &self,
         alpha: f32,
@@ -18,6 +20,7 @@ impl Kernel for NeonKernel8x8<f32> {
     ) {
         dbg_check_microkernel_inputs(self, lhs, rhs, dst);
         let kc = lhs.ncols();
+        let col_stride = dst.col_stride();
         neon_8x8_microkernel_f32(
             kc,
             alpha,
@@ -25,6 +28,7 @@ impl Kernel for NeonKernel8x8<f32> {
             rhs.as_slice(),
             beta,
             dst.as_mut_slice(),
+            col_stride,
         );
     }
 }
@@ -35,12 +39,14 @@ fn neon_8x8_microkernel_f32(
     lhs: &[f32],
     rhs: &[f32],
     beta: f32,
-    dst_colmajor: &mut [f32],
+    dst: &mut [f32],
+    col_stride: usize,
 ) {
     const DIM: usize = 8;
     assert_eq!(lhs.len(), rhs.len());
     assert_eq!(lhs.len(), DIM.checked_mul(kc).unwrap());
-    assert_eq!(dst_colmajor.len(), DIM * DIM);
+    assert!(col_stride >= DIM);
+    assert_eq!(dst.len(), (DIM - 1) * col_stride + DIM);
 
     unsafe {
         inner(
@@ -49,11 +55,20 @@ fn neon_8x8_microkernel_f32(
             lhs.as_ptr(),
             rhs.as_ptr(),
             beta,
-            dst_colmajor.as_mut_ptr(),
+            dst.as_mut_ptr(),
+            col_stride,
         )
     };
 
-    unsafe fn inner(kc: usize, alpha: f32, a: *const f32, b: *const f32, beta: f32, c: *mut f32) {
+    unsafe fn inner(
+        kc: usize,
+        alpha: f32,
+        a: *const f32,
+        b: *const f32,
+        beta: f32,
+        c: *mut f32,
+        col_stride: usize,
+    ) {
         let (mut a, mut b) = (b, a);
 
         let mut ab11 = [vmovq_n_f32(0f32); 4];
@@ -96,7 +111,7 @@ fn neon_8x8_microkernel_f32(
 
         macro_rules! c {
             ($i:expr, $j:expr) => {
-                c.add(8 * $i + $j)
+                c.add(col_stride * $i + $j)
             };
         }
 
@@ -19,12 +19,25 @@ mod simd {
 pub use ker4x4::NeonKernel4x4;
 pub use ker8x8::NeonKernel8x8;
 
+/// Checks `neon` support at runtime if the `std` feature is enabled,
+/// otherwise falls back to the compile-time target features.
+#[allow(dead_code)]
+pub(crate) fn is_neon_available() -> bool {
+    #[cfg(all(feature = "std", target_arch = "aarch64"))]
+    {
+        std::arch::is_aarch64_feature_detected!("neon")
+    }
+    #[cfg(not(all(feature = "std", target_arch = "aarch64")))]
+    {
+        cfg!(target_feature = "neon")
+    }
+}
+
 #[cfg(not(miri))]
 #[cfg(test)]
 mod proptests {
     use super::*;
-    use crate::utils::{assert_approx_eq, proptest_kernel, ProptestKernelCfg};
-    use proptest::{strategy::Strategy, test_runner::TestCaseResult};
+    use crate::{testing::proptest_kernel, utils::simd_cfg};
     use std::arch::is_aarch64_feature_detected;
 
     fn neon_kernel_8x8<T>() -> NeonKernel8x8<T> {
@@ -42,26 +55,12 @@ mod proptests {
         }
     }
 
-    fn cfg_f32() -> ProptestKernelCfg<f32> {
-        let cmp = |expect: &[f32], got: &[f32]| -> TestCaseResult {
-            let eps = 75.0 * f32::EPSILON;
-            assert_approx_eq(expect, got, eps);
-            Ok(())
-        };
-        let dim = 80;
-        ProptestKernelCfg::default()
-            .with_cmp(cmp)
-            .with_scalar((-1f32..1.0).boxed())
-            .with_max_matrix_dim(dim)
-            .with_max_pack_dim(2 * dim + 1)
-    }
-
     #[test]
     fn proptest_neon_kernel_8x8_f32() {
-        proptest_kernel(&neon_kernel_8x8(), cfg_f32()).unwrap();
+        proptest_kernel(&neon_kernel_8x8(), simd_cfg::<f32>()).unwrap();
     }
     #[test]
     fn proptest_neon_kernel_4x4_f32() {
-        proptest_kernel(&neon_kernel_4x4(), cfg_f32()).unwrap();
+        proptest_kernel(&neon_kernel_4x4(), simd_cfg::<f32>()).unwrap();
     }
 }
@@ -0,0 +1,149 @@
+use super::Sse2Kernel4x2;
+use crate::{
+    kernels::dbg_check_microkernel_inputs,
+    typenum::{U2, U4},
+    Kernel, MatMut, MatRef,
+};
+
+use super::super::simd::*;
+
+impl Kernel for Sse2Kernel4x2<f64> {
+    type Scalar = f64;
+    type Mr = U4;
+    type Nr = U2;
+
+    const STRIDED_DST: bool = true;
+
+    fn microkernel(
+        &self,
+        alpha: f64,
+        lhs: MatRef<f64>,
+        rhs: MatRef<f64>,
+        beta: f64,
+        dst: &mut MatMut<f64>,
+    ) {
+        dbg_check_microkernel_inputs(self, lhs, rhs, dst);
+        let kc = lhs.ncols();
+        let col_stride = dst.col_stride();
+        sse2_4x2_microkernel_f64(
+            kc,
+            alpha,
+            lhs.as_slice(),
+            rhs.as_slice(),
+            beta,
+            dst.as_mut_slice(),
+            col_stride,
+        );
+    }
+}
+
+fn sse2_4x2_microkernel_f64(
+    kc: usize,
+    alpha: f64,
+    lhs: &[f64],
+    rhs: &[f64],
+    beta: f64,
+    dst: &mut [f64],
+    col_stride: usize,
+) {
+    const MR: usize = 4;
+    const NR: usize = 2;
+    assert_eq!(lhs.len(), MR.checked_mul(kc).unwrap());
+    assert_eq!(rhs.len(), NR.checked_mul(kc).unwrap());
+    assert!(col_stride >= MR);
+    assert_eq!(dst.len(), (NR - 1) * col_stride + MR);
+
+    unsafe {
+        inner(
+            kc,
+            alpha,
+            lhs.as_ptr(),
+            rhs.as_ptr(),
+            beta,
+            dst.as_mut_ptr(),
+            col_stride,
+        )
+    };
+
+    unsafe fn inner(
+        kc: usize,
+        alpha: f64,
+        mut a: *const f64,
+        mut b: *const f64,
+        beta: f64,
+        c: *mut f64,
+        col_stride: usize,
+    ) {
+        // upper and lower halves of the 2 columns
+        let mut cols1 = [_mm_setzero_pd(); 2];
+        let mut cols2 = [_mm_setzero_pd(); 2];
+
+        // Compute
+        // col_j += a * b_j for all j
+        for _ in 0..kc {
+            let a1 = _mm_loadu_pd(a);
+            let a2 = _mm_loadu_pd(a.add(2));
+            for j in 0..2 {
+                let bv = _mm_set1_pd(*b.add(j));
+                cols1[j] = _mm_add_pd(cols1[j], _mm_mul_pd(a1, bv));
+                cols2[j] = _mm_add_pd(cols2[j], _mm_mul_pd(a2, bv));
+            }
+            a = a.add(4);
+            b = b.add(2);
+        }
+
+        let alphav = _mm_set1_pd(alpha);
+        let betav = _mm_set1_pd(beta);
+        for j in 0..2 {
+            let to1 = c.add(col_stride * j);
+            let to2 = to1.add(2);
+            let ab1 = _mm_mul_pd(cols1[j], alphav);
+            let ab2 = _mm_mul_pd(cols2[j], alphav);
+            _mm_storeu_pd(to1, _mm_add_pd(ab1, _mm_mul_pd(_mm_loadu_pd(to1), betav)));
+            _mm_storeu_pd(to2, _mm_add_pd(ab2, _mm_mul_pd(_mm_loadu_pd(to2), betav)));
+        }
+    }
+}
+
+#[cfg(kani)]
+mod proofs {
+    use super::*;
+
+    const MR: usize = 4;
+    const NR: usize = 2;
+
+    const fn max(a: usize, b: usize) -> usize {
+        if a < b {
+            b
+        } else {
+            a
+        }
+    }
+
+    #[kani::proof]
+    #[kani::unwind(5)] // 1 + max(2, kc)
+    fn check_sse2_4x2_microkernel_f64() -> Option<()> {
+        const KC_LIMIT: usize = 4;
+        const MAX_VEC_LEN: usize = 3 + max(MR * KC_LIMIT, NR * (MR + 1));
+
+        let kc: usize = kani::any_where(|&kc| kc <= KC_LIMIT);
+        let alpha: f64 = kani::any();
+        let beta: f64 = kani::any();
+
+        let left = kani::vec::any_vec::<f64, MAX_VEC_LEN>();
+        let right = kani::vec::any_vec::<f64, MAX_VEC_LEN>();
+        kani::assume(left.len() >= MR * kc);
+        kani::assume(right.len() >= NR * kc);
+        let left = &left[..MR * kc];
+        let right = &right[..NR * kc];
+
+        let col_stride: usize = kani::any_where(|&cs| (MR..=MR + 1).contains(&cs));
+        let dst_len = (NR - 1) * col_stride + MR;
+        let mut dst = kani::vec::any_vec::<f64, MAX_VEC_LEN>();
+        kani::assume(dst.len() >= dst_len);
+        let dst = &mut dst[..dst_len];
+
+        sse2_4x2_microkernel_f64(kc, alpha, left, right, beta, dst, col_stride);
+        Some(())
+    }
+}
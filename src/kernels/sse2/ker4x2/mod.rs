@@ -0,0 +1,27 @@
+#[cfg(target_arch = "x86_64")]
+mod f64_4x2;
+
+use core::marker::PhantomData;
+
+/// Available only for the `x86_64` target.
+///
+/// `sse2` is a part of the `x86_64` baseline, so no runtime detection is needed.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Sse2Kernel4x2<T> {
+    marker: PhantomData<T>,
+}
+
+impl<T> Sse2Kernel4x2<T> {
+    /// # Examples
+    ///
+    /// ```
+    /// use microgemm::kernels::Sse2Kernel4x2;
+    ///
+    /// let kernel = Sse2Kernel4x2::<f64>::new();
+    /// ```
+    pub const fn new() -> Self {
+        Self {
+            marker: PhantomData,
+        }
+    }
+}
@@ -0,0 +1,65 @@
+mod ker4x2;
+mod ker4x4;
+
+#[cfg(any(kani, miri))]
+mod simd_mock;
+
+#[cfg(target_arch = "x86_64")]
+mod simd {
+    #[cfg(any(kani, miri))]
+    pub use super::simd_mock::{
+        _mm_add_pd, _mm_add_ps, _mm_loadu_pd, _mm_loadu_ps, _mm_mul_pd, _mm_mul_ps, _mm_set1_pd,
+        _mm_set1_ps, _mm_setzero_pd, _mm_setzero_ps, _mm_storeu_pd, _mm_storeu_ps,
+    };
+    #[cfg(not(any(kani, miri)))]
+    pub use core::arch::x86_64::{
+        _mm_add_pd, _mm_add_ps, _mm_loadu_pd, _mm_loadu_ps, _mm_mul_pd, _mm_mul_ps, _mm_set1_pd,
+        _mm_set1_ps, _mm_setzero_pd, _mm_setzero_ps, _mm_storeu_pd, _mm_storeu_ps,
+    };
+}
+
+pub use ker4x2::Sse2Kernel4x2;
+pub use ker4x4::Sse2Kernel4x4;
+
+#[cfg(not(miri))]
+#[cfg(test)]
+mod proptests {
+    use super::*;
+    use crate::{testing::proptest_kernel, utils::simd_cfg};
+
+    #[test]
+    fn proptest_sse2_kernel_4x4_f32() {
+        proptest_kernel(&Sse2Kernel4x4::new(), simd_cfg::<f32>()).unwrap();
+    }
+    #[test]
+    fn proptest_sse2_kernel_4x2_f64() {
+        proptest_kernel(&Sse2Kernel4x2::new(), simd_cfg::<f64>()).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{std_prelude::*, utils::naive_gemm, DynKernel as _, MatMut, MatRef};
+
+    #[test]
+    fn test_sse2_kernel_strided_dst() {
+        let kernel = Sse2Kernel4x2::new();
+        let kc = 3;
+        let lhs = (0..4 * kc).map(|x| x as f64).collect::<Vec<_>>();
+        let rhs = (0..kc * 2).map(|x| x as f64 - 2.0).collect::<Vec<_>>();
+
+        // the tile is the submatrix [1..5, 0..2] of a col-major 6 x 2 matrix
+        let mut values = (0..6 * 2).map(|x| x as f64).collect::<Vec<_>>();
+        let mut expect = values.clone();
+        let mut expect = MatMut::col_major(6, 2, &mut expect);
+        let lhs_mat = MatRef::col_major(4, kc, &lhs);
+        let rhs_mat = MatRef::row_major(kc, 2, &rhs);
+        let mut tile = expect.submatrix_mut(1..5, 0..2);
+        naive_gemm(2.0, lhs_mat, rhs_mat, -1.0, &mut tile);
+
+        kernel.dyn_microkernel_strided(2.0, &lhs, &rhs, -1.0, &mut values[1..11], 6);
+        assert_eq!(values, expect.as_slice());
+        assert!(kernel.dyn_strided_dst());
+    }
+}
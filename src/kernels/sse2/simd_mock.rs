@@ -0,0 +1,122 @@
+const F32_BOUND: f32 = 1e3;
+const F64_BOUND: f64 = 1e3;
+
+fn add_f32(a: f32, b: f32) -> f32 {
+    #[cfg(kani)]
+    {
+        kani::assume(a.abs() < F32_BOUND);
+        kani::assume(b.abs() < F32_BOUND);
+    }
+    a + b
+}
+
+fn mul_f32(a: f32, b: f32) -> f32 {
+    #[cfg(kani)]
+    {
+        kani::assume(a.abs() < F32_BOUND);
+        kani::assume(b.abs() < F32_BOUND);
+    }
+    a * b
+}
+
+fn add_f64(a: f64, b: f64) -> f64 {
+    #[cfg(kani)]
+    {
+        kani::assume(a.abs() < F64_BOUND);
+        kani::assume(b.abs() < F64_BOUND);
+    }
+    a + b
+}
+
+fn mul_f64(a: f64, b: f64) -> f64 {
+    #[cfg(kani)]
+    {
+        kani::assume(a.abs() < F64_BOUND);
+        kani::assume(b.abs() < F64_BOUND);
+    }
+    a * b
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy)]
+pub struct __m128([f32; 4]);
+
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy)]
+pub struct __m128d([f64; 2]);
+
+pub unsafe fn _mm_setzero_ps() -> __m128 {
+    _mm_set1_ps(0f32)
+}
+
+pub unsafe fn _mm_set1_ps(value: f32) -> __m128 {
+    __m128([value; 4])
+}
+
+pub unsafe fn _mm_loadu_ps(ptr: *const f32) -> __m128 {
+    let mut out = _mm_setzero_ps();
+    for i in 0..4 {
+        out.0[i] = *ptr.add(i);
+    }
+    out
+}
+
+pub unsafe fn _mm_storeu_ps(ptr: *mut f32, a: __m128) {
+    for i in 0..4 {
+        *ptr.add(i) = a.0[i];
+    }
+}
+
+pub unsafe fn _mm_add_ps(__m128(a): __m128, __m128(b): __m128) -> __m128 {
+    let mut out = _mm_setzero_ps();
+    for i in 0..4 {
+        out.0[i] = add_f32(a[i], b[i]);
+    }
+    out
+}
+
+pub unsafe fn _mm_mul_ps(__m128(a): __m128, __m128(b): __m128) -> __m128 {
+    let mut out = _mm_setzero_ps();
+    for i in 0..4 {
+        out.0[i] = mul_f32(a[i], b[i]);
+    }
+    out
+}
+
+pub unsafe fn _mm_setzero_pd() -> __m128d {
+    _mm_set1_pd(0f64)
+}
+
+pub unsafe fn _mm_set1_pd(value: f64) -> __m128d {
+    __m128d([value; 2])
+}
+
+pub unsafe fn _mm_loadu_pd(ptr: *const f64) -> __m128d {
+    let mut out = _mm_setzero_pd();
+    for i in 0..2 {
+        out.0[i] = *ptr.add(i);
+    }
+    out
+}
+
+pub unsafe fn _mm_storeu_pd(ptr: *mut f64, a: __m128d) {
+    for i in 0..2 {
+        *ptr.add(i) = a.0[i];
+    }
+}
+
+pub unsafe fn _mm_add_pd(__m128d(a): __m128d, __m128d(b): __m128d) -> __m128d {
+    let mut out = _mm_setzero_pd();
+    for i in 0..2 {
+        out.0[i] = add_f64(a[i], b[i]);
+    }
+    out
+}
+
+pub unsafe fn _mm_mul_pd(__m128d(a): __m128d, __m128d(b): __m128d) -> __m128d {
+    let mut out = _mm_setzero_pd();
+    for i in 0..2 {
+        out.0[i] = mul_f64(a[i], b[i]);
+    }
+    out
+}
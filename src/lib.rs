@@ -61,9 +61,65 @@ println!("{:?}", c.as_slice());
 | GenericKernelNxN <br> (N: 2, 4, 8, 16, 32) | T: Copy + Zero + One + Mul + Add | Any |
 | [`NeonKernel4x4`] | f32 | aarch64 and target feature neon |
 | [`NeonKernel8x8`] | f32 | aarch64 and target feature neon |
+| [`Sse2Kernel4x4`] | f32 | x86_64 |
+| [`Sse2Kernel4x2`] | f64 | x86_64 |
+| [`Avx2FmaKernel8x8`] | f32 | x86_64 and target features avx2, fma |
+| [`Avx2FmaKernel8x4`] | f64 | x86_64 and target features avx2, fma |
+| [`AutoKernel`] <br> (runtime dispatch) | f32, f64 | Any |
 
 [`NeonKernel4x4`]: crate::kernels::NeonKernel4x4
 [`NeonKernel8x8`]: crate::kernels::NeonKernel8x8
+[`Sse2Kernel4x4`]: crate::kernels::Sse2Kernel4x4
+[`Sse2Kernel4x2`]: crate::kernels::Sse2Kernel4x2
+[`Avx2FmaKernel8x8`]: crate::kernels::Avx2FmaKernel8x8
+[`Avx2FmaKernel8x4`]: crate::kernels::Avx2FmaKernel8x4
+[`AutoKernel`]: crate::kernels::AutoKernel
+
+### Multithreading
+
+With the optional `rayon` feature, `Kernel::par_gemm` packs each panel of `b` once and computes
+blocks of tiles of `c` in parallel on the current rayon thread pool. The default build stays `no_std`.
+```sh
+cargo add microgemm --features rayon
+```
+
+In `no_std` environments, [`Kernel::gemm_with_executor`] runs the workers of a user-supplied [`Executor`],
+which share each packed panel of `b`, with a caller-provided workspace of
+`PackSizes::par_buf_len(workers)` elements.
+
+[`Kernel::gemm_with_executor`]: crate::Kernel::gemm_with_executor
+[`Executor`]: crate::Executor
+
+### Mixed Precision
+
+[`Kernel::gemm_mixed`](crate::Kernel::gemm_mixed) multiplies `a` and `b` of a narrower type, such as `half::f16` or `half::bf16`,
+with a kernel of `f32`: they are converted while being packed,
+so the sums are accumulated and written to `c` in `f32`.
+[`Kernel::gemm_mixed_into`](crate::Kernel::gemm_mixed_into) writes them to a `c` of another type, such as `f64`,
+converting each tile when it is written back.
+
+### Epilogue
+
+`Kernel::gemm_with_epilogue` fuses an elementwise [`Epilogue`](crate::Epilogue), such as a bias and an activation,
+into gemm: it is applied to each tile of `c` while it is still in registers,
+instead of in a second pass over `c`.
+```rust
+use microgemm::{epilogue::{Relu, RowBias}, kernels::GenericKernel8x8, Epilogue as _, Kernel as _};
+# use microgemm::{MatMut, MatRef, PackSizes};
+# let kernel = GenericKernel8x8::<f32>::new();
+# let pack_sizes = PackSizes { mc: 8, kc: 8, nc: 8 };
+# let mut packing_buf = vec![0.0; pack_sizes.buf_len()];
+# let a = MatRef::row_major(2, 1, &[1.0, -1.0]);
+# let b = MatRef::row_major(1, 1, &[1.0]);
+# let mut c = [0.0; 2];
+# let mut c = MatMut::row_major(2, 1, &mut c);
+let bias = [0.5, 0.5];
+
+// c <- relu(a b + bias)
+let epilogue = RowBias(&bias).then(Relu);
+kernel.gemm_with_epilogue(1.0, a, b, 0.0, &mut c, pack_sizes, &mut packing_buf, epilogue);
+# assert_eq!(c.as_slice(), [1.5, 0.0]);
+```
 
 ### Custom Kernel Implementation
 
@@ -104,6 +160,19 @@ impl Kernel for CustomKernel {
 }
 ```
 
+### Testing Custom Kernels
+
+With the `testing` feature, `testing::proptest_kernel` checks a kernel against a naive gemm
+on arbitrary matrices and `PackSizes`.
+```rust,ignore
+use microgemm::testing::{proptest_kernel, relative_eq, ProptestKernelCfg};
+
+let cfg = ProptestKernelCfg::default()
+    .with_cmp(relative_eq(16.0 * f64::EPSILON, f64::EPSILON))
+    .with_max_matrix_dim(40);
+proptest_kernel(&CustomKernel, cfg).unwrap();
+```
+
 ## Benchmarks
 
 All benchmarks are performed in a `single thread` on square matrices of dimension `n`.
@@ -128,33 +197,52 @@ All benchmarks are performed in a `single thread` on square matrices of dimensio
 #[macro_use]
 extern crate approx;
 
-#[cfg(any(test, kani))]
+#[cfg(any(test, kani, feature = "std"))]
 #[macro_use]
 extern crate std;
 
-#[cfg(any(test, kani))]
+#[cfg(any(test, kani, feature = "std"))]
+#[allow(unused_imports)]
 mod std_prelude {
     pub use std::prelude::rust_2021::*;
 }
 
-#[cfg(test)]
-use allocator_api2::alloc::Global as GlobalAllocator;
-
+mod batched;
+mod error;
+mod executor;
 mod gemm;
 mod kernel;
+mod mixed;
+#[cfg(feature = "rayon")]
+mod par;
+mod par_loops;
+mod prepacked;
+mod small;
 
 pub(crate) mod packing;
 #[cfg(test)]
 pub(crate) mod utils;
 
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+
+#[cfg(feature = "std")]
+pub mod autotune;
+pub mod epilogue;
 pub mod kernels;
 pub mod mat;
+pub mod reference;
 
 pub use generic_array::typenum;
 pub use num_traits::{One, Zero};
 
-pub(crate) use gemm::gemm_with_kernel;
+pub(crate) use gemm::{gemm_with_kernel, try_gemm_with_kernel};
 
-pub use kernel::Kernel;
+pub use epilogue::Epilogue;
+pub use error::GemmError;
+pub use executor::Executor;
+pub use gemm::gemm_dyn;
+pub use kernel::{DynKernel, Kernel};
 pub use mat::{MatMut, MatRef};
-pub use packing::PackSizes;
+pub use packing::{CacheInfo, PackSizes};
+pub use prepacked::{PackedA, PackedB};
@@ -24,6 +24,29 @@ impl<V, T> MatBase<V, T> {
     pub fn col_stride(&self) -> usize {
         self.col_stride
     }
+    /// Returns the transposed matrix without copying the values,
+    /// by swapping the dimensions and the strides.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use microgemm::MatRef;
+    ///
+    /// let values = [1, 2, 3, 4, 5, 6];
+    /// let mat = MatRef::row_major(2, 3, &values);
+    /// let t = mat.t();
+    /// assert_eq!([t.nrows(), t.ncols()], [3, 2]);
+    /// assert_eq!(t.get(2, 0), mat.get(0, 2));
+    /// ```
+    pub fn t(self) -> Self {
+        Self {
+            nrows: self.ncols,
+            ncols: self.nrows,
+            row_stride: self.col_stride,
+            col_stride: self.row_stride,
+            ..self
+        }
+    }
     pub(crate) fn checked_idx(&self, row: usize, col: usize) -> Option<usize> {
         if !self.in_bounds(row, col) {
             return None;
@@ -48,7 +71,9 @@ where
     V: AsRef<[T]>,
 {
     /// Creates a matrix from a given number of rows/columns, values and strides.
-    /// Returns `None` if the last index overflows.
+    /// Returns `None` if the last index overflows or is out of `values`.
+    ///
+    /// Matrices with zero rows or columns are valid and may have empty `values`.
     pub fn from_parts(
         nrows: usize,
         ncols: usize,
@@ -64,9 +89,10 @@ where
             col_stride,
             marker: PhantomData,
         };
-        let last_row = nrows.checked_sub(1)?;
-        let last_col = ncols.checked_sub(1)?;
-        let last_idx = mat.checked_idx(last_row, last_col)?;
+        if nrows == 0 || ncols == 0 {
+            return Some(mat);
+        }
+        let last_idx = mat.checked_idx(nrows - 1, ncols - 1)?;
         if last_idx < mat.as_slice().len() {
             Some(mat)
         } else {
@@ -256,4 +282,41 @@ mod tests {
         ];
         assert_eq!(mat.as_slice(), expect);
     }
+    #[rustfmt::skip]
+    #[test]
+    fn test_transpose() {
+        let values = [
+            1, 2, 3,
+            4, 5, 6,
+        ];
+        let mat = MatRef::row_major(2, 3, &values);
+        let t = mat.t();
+        assert_eq!([t.nrows(), t.ncols()], [3, 2]);
+        assert_eq!([t.row_stride(), t.col_stride()], [1, 3]);
+        for row in 0..2 {
+            for col in 0..3 {
+                assert_eq!(mat.get(row, col), t.get(col, row));
+            }
+        }
+        assert_eq!(t.t().as_slice(), mat.as_slice());
+
+        let mut values = values;
+        let mut t = MatMut::col_major(3, 2, &mut values).t();
+        *t.get_mut(1, 0) = -2;
+        assert_eq!(t.as_slice(), [1, 2, 3, -2, 5, 6]);
+    }
+
+    #[test]
+    fn test_empty() {
+        let mat = MatRef::<i32>::row_major(0, 3, &[]);
+        assert_eq!([mat.nrows(), mat.ncols()], [0, 3]);
+        let mat = MatRef::<i32>::col_major(2, 0, &[]);
+        assert_eq!([mat.nrows(), mat.ncols()], [2, 0]);
+        assert!(MatRef::from_parts(0, 0, &[1, 2][..], 7, 9).is_some());
+
+        let mut values = [];
+        let mut mat = MatMut::<i32>::row_major(4, 0, &mut values);
+        assert!(mat.as_mut_slice().is_empty());
+        assert_eq!(mat.to_ref().nrows(), 4);
+    }
 }
@@ -1,4 +1,5 @@
 pub mod base;
+mod view;
 
 pub type MatRef<'a, T> = base::MatBase<&'a [T], T>;
 pub type MatMut<'a, T> = base::MatBase<&'a mut [T], T>;
@@ -0,0 +1,442 @@
+use super::{base::MatBase, MatMut, MatRef};
+use core::ops::Range;
+
+// Length of the smallest slice holding a matrix with the given dimensions and strides.
+fn span(nrows: usize, ncols: usize, row_stride: usize, col_stride: usize) -> usize {
+    if nrows == 0 || ncols == 0 {
+        0
+    } else {
+        (nrows - 1) * row_stride + (ncols - 1) * col_stride + 1
+    }
+}
+
+// Whether the rows do not interleave in memory, so that they can be borrowed separately.
+fn rows_are_separable(nrows: usize, ncols: usize, row_stride: usize, col_stride: usize) -> bool {
+    nrows <= 1 || span(1, ncols, row_stride, col_stride) <= row_stride
+}
+
+fn cols_are_separable(nrows: usize, ncols: usize, row_stride: usize, col_stride: usize) -> bool {
+    ncols <= 1 || span(nrows, 1, row_stride, col_stride) <= col_stride
+}
+
+// Whether splitting at `row` gives two halves that do not share memory.
+fn splits_at_row(nrows: usize, ncols: usize, rs: usize, cs: usize, row: usize) -> bool {
+    row == 0 || row == nrows || rows_are_separable(nrows, ncols, rs, cs)
+}
+
+fn splits_at_col(nrows: usize, ncols: usize, rs: usize, cs: usize, col: usize) -> bool {
+    col == 0 || col == ncols || cols_are_separable(nrows, ncols, rs, cs)
+}
+
+impl<V, T> MatBase<V, T> {
+    // Length of the smallest slice holding the matrix.
+    pub(crate) fn span(&self) -> usize {
+        span(self.nrows, self.ncols, self.row_stride, self.col_stride)
+    }
+}
+
+// Range of `values` holding the submatrix [rows, cols], its first element at the start.
+fn sub_range(
+    nrows: usize,
+    ncols: usize,
+    row_stride: usize,
+    col_stride: usize,
+    rows: &Range<usize>,
+    cols: &Range<usize>,
+) -> Range<usize> {
+    assert!(
+        rows.start <= rows.end && rows.end <= nrows,
+        "rows out of bounds"
+    );
+    assert!(
+        cols.start <= cols.end && cols.end <= ncols,
+        "cols out of bounds"
+    );
+    let len = span(rows.len(), cols.len(), row_stride, col_stride);
+    if len == 0 {
+        return 0..0;
+    }
+    let offset = rows.start * row_stride + cols.start * col_stride;
+    offset..offset + len
+}
+
+impl<'a, T> MatRef<'a, T> {
+    /// Returns a view of the submatrix `[rows, cols]` without copying the values.
+    ///
+    /// # Panics
+    /// Panics if `rows` or `cols` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use microgemm::MatRef;
+    ///
+    /// let values = [1, 2, 3, 4, 5, 6];
+    /// let mat = MatRef::row_major(2, 3, &values);
+    /// let sub = mat.submatrix(0..2, 1..3);
+    /// assert_eq!([sub.nrows(), sub.ncols()], [2, 2]);
+    /// assert_eq!(sub.get(1, 0), 5);
+    /// ```
+    pub fn submatrix(self, rows: Range<usize>, cols: Range<usize>) -> Self {
+        let (rs, cs) = (self.row_stride, self.col_stride);
+        let range = sub_range(self.nrows, self.ncols, rs, cs, &rows, &cols);
+        let values = &self.values[range];
+        MatRef::from_parts(rows.len(), cols.len(), values, rs, cs).unwrap()
+    }
+    /// Returns a `1 x ncols` view of the `i`-th row.
+    ///
+    /// # Panics
+    /// Panics if `i >= nrows`.
+    pub fn row(self, i: usize) -> Self {
+        let ncols = self.ncols;
+        self.submatrix(i..i + 1, 0..ncols)
+    }
+    /// Returns a `nrows x 1` view of the `j`-th column.
+    ///
+    /// # Panics
+    /// Panics if `j >= ncols`.
+    pub fn col(self, j: usize) -> Self {
+        let nrows = self.nrows;
+        self.submatrix(0..nrows, j..j + 1)
+    }
+    // The matrix with the same layout, starting `offset` values later.
+    pub(crate) fn offset_by(self, offset: usize) -> Self {
+        let (rs, cs) = (self.row_stride, self.col_stride);
+        MatRef::from_parts(self.nrows, self.ncols, &self.values[offset..], rs, cs).unwrap()
+    }
+}
+
+impl<'a, T> MatMut<'a, T> {
+    /// Returns a mutable view of the submatrix `[rows, cols]` without copying the values.
+    ///
+    /// # Panics
+    /// Panics if `rows` or `cols` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use microgemm::MatMut;
+    ///
+    /// let mut values = [1, 2, 3, 4, 5, 6];
+    /// let mut mat = MatMut::row_major(2, 3, &mut values);
+    /// let mut sub = mat.submatrix_mut(1..2, 1..3);
+    /// *sub.get_mut(0, 1) = 0;
+    /// assert_eq!(values, [1, 2, 3, 4, 5, 0]);
+    /// ```
+    pub fn submatrix_mut(&mut self, rows: Range<usize>, cols: Range<usize>) -> MatMut<'_, T> {
+        let (rs, cs) = (self.row_stride, self.col_stride);
+        let range = sub_range(self.nrows, self.ncols, rs, cs, &rows, &cols);
+        let values = &mut self.values[range];
+        MatMut::from_parts(rows.len(), cols.len(), values, rs, cs).unwrap()
+    }
+    /// Returns a mutable `1 x ncols` view of the `i`-th row.
+    ///
+    /// # Panics
+    /// Panics if `i >= nrows`.
+    pub fn row_mut(&mut self, i: usize) -> MatMut<'_, T> {
+        let ncols = self.ncols;
+        self.submatrix_mut(i..i + 1, 0..ncols)
+    }
+    /// Returns a mutable `nrows x 1` view of the `j`-th column.
+    ///
+    /// # Panics
+    /// Panics if `j >= ncols`.
+    pub fn col_mut(&mut self, j: usize) -> MatMut<'_, T> {
+        let nrows = self.nrows;
+        self.submatrix_mut(0..nrows, j..j + 1)
+    }
+    /// Splits the matrix into the rows `0..row` and `row..nrows`.
+    ///
+    /// The two halves borrow disjoint parts of the values,
+    /// which requires the rows not to interleave in memory,
+    /// i.e. `(ncols - 1) * col_stride < row_stride` (e.g. a row-major matrix).
+    ///
+    /// # Panics
+    /// 1. If `row > nrows`.
+    /// 2. If the rows interleave in memory, e.g. for a col-major matrix.
+    ///    Use [`split_at_col_mut`](Self::split_at_col_mut) instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use microgemm::MatMut;
+    ///
+    /// let mut values = [1, 2, 3, 4, 5, 6];
+    /// let mut mat = MatMut::row_major(3, 2, &mut values);
+    /// let (mut top, mut bottom) = mat.split_at_row_mut(1);
+    /// *top.get_mut(0, 0) = 0;
+    /// *bottom.get_mut(1, 1) = 0;
+    /// assert_eq!(values, [0, 2, 3, 4, 5, 0]);
+    /// ```
+    pub fn split_at_row_mut(&mut self, row: usize) -> (MatMut<'_, T>, MatMut<'_, T>) {
+        self.reborrow().into_split_at_row(row)
+    }
+    /// Splits the matrix into the columns `0..col` and `col..ncols`.
+    ///
+    /// The two halves borrow disjoint parts of the values,
+    /// which requires the columns not to interleave in memory,
+    /// i.e. `(nrows - 1) * row_stride < col_stride` (e.g. a col-major matrix).
+    ///
+    /// # Panics
+    /// 1. If `col > ncols`.
+    /// 2. If the columns interleave in memory, e.g. for a row-major matrix.
+    ///    Use [`split_at_row_mut`](Self::split_at_row_mut) instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use microgemm::MatMut;
+    ///
+    /// let mut values = [1, 2, 3, 4, 5, 6];
+    /// let mut mat = MatMut::col_major(2, 3, &mut values);
+    /// let (mut left, mut right) = mat.split_at_col_mut(2);
+    /// *left.get_mut(1, 1) = 0;
+    /// *right.get_mut(0, 0) = 0;
+    /// assert_eq!(values, [1, 2, 3, 0, 0, 6]);
+    /// ```
+    pub fn split_at_col_mut(&mut self, col: usize) -> (MatMut<'_, T>, MatMut<'_, T>) {
+        self.reborrow().into_split_at_col(col)
+    }
+    /// Non-panicking version of [`split_at_row_mut`](Self::split_at_row_mut),
+    /// returns `None` if the rows interleave in memory.
+    ///
+    /// # Panics
+    /// If `row > nrows`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use microgemm::MatMut;
+    ///
+    /// let mut values = [1, 2, 3, 4, 5, 6];
+    /// let mut mat = MatMut::col_major(3, 2, &mut values);
+    /// assert!(mat.try_split_at_row_mut(1).is_none());
+    /// let (left, right) = mat.try_split_at_col_mut(1).unwrap();
+    /// assert_eq!([left.as_slice(), right.as_slice()], [[1, 2, 3], [4, 5, 6]]);
+    /// ```
+    pub fn try_split_at_row_mut(&mut self, row: usize) -> Option<(MatMut<'_, T>, MatMut<'_, T>)> {
+        assert!(row <= self.nrows, "row out of bounds");
+        let [nrows, ncols] = [self.nrows, self.ncols];
+        splits_at_row(nrows, ncols, self.row_stride, self.col_stride, row)
+            .then(|| self.reborrow().into_split_at_row(row))
+    }
+    /// Non-panicking version of [`split_at_col_mut`](Self::split_at_col_mut),
+    /// returns `None` if the columns interleave in memory.
+    ///
+    /// # Panics
+    /// If `col > ncols`.
+    pub fn try_split_at_col_mut(&mut self, col: usize) -> Option<(MatMut<'_, T>, MatMut<'_, T>)> {
+        assert!(col <= self.ncols, "col out of bounds");
+        let [nrows, ncols] = [self.nrows, self.ncols];
+        splits_at_col(nrows, ncols, self.row_stride, self.col_stride, col)
+            .then(|| self.reborrow().into_split_at_col(col))
+    }
+    pub(crate) fn reborrow(&mut self) -> MatMut<'_, T> {
+        let (rs, cs) = (self.row_stride, self.col_stride);
+        MatMut::from_parts(self.nrows, self.ncols, &mut *self.values, rs, cs).unwrap()
+    }
+    pub(crate) fn offset_by_mut(&mut self, offset: usize) -> MatMut<'_, T> {
+        let (rs, cs) = (self.row_stride, self.col_stride);
+        let values = &mut self.values[offset..];
+        MatMut::from_parts(self.nrows, self.ncols, values, rs, cs).unwrap()
+    }
+    pub(crate) fn rows_are_separable(&self) -> bool {
+        rows_are_separable(self.nrows, self.ncols, self.row_stride, self.col_stride)
+    }
+    pub(crate) fn cols_are_separable(&self) -> bool {
+        cols_are_separable(self.nrows, self.ncols, self.row_stride, self.col_stride)
+    }
+    pub(crate) fn into_split_at_row(self, row: usize) -> (Self, Self) {
+        let [nrows, ncols] = [self.nrows, self.ncols];
+        let (rs, cs) = (self.row_stride, self.col_stride);
+        assert!(row <= nrows, "row out of bounds");
+        assert!(
+            splits_at_row(nrows, ncols, rs, cs, row),
+            "rows interleave in memory"
+        );
+        split_mut(
+            self.values,
+            [row, ncols],
+            [nrows - row, ncols],
+            // the first half takes all the values if the second one is empty
+            if row == nrows { usize::MAX } else { row * rs },
+            rs,
+            cs,
+        )
+    }
+    pub(crate) fn into_split_at_col(self, col: usize) -> (Self, Self) {
+        let [nrows, ncols] = [self.nrows, self.ncols];
+        let (rs, cs) = (self.row_stride, self.col_stride);
+        assert!(col <= ncols, "col out of bounds");
+        assert!(
+            splits_at_col(nrows, ncols, rs, cs, col),
+            "cols interleave in memory"
+        );
+        split_mut(
+            self.values,
+            [nrows, col],
+            [nrows, ncols - col],
+            // the first half takes all the values if the second one is empty
+            if col == ncols { usize::MAX } else { col * cs },
+            rs,
+            cs,
+        )
+    }
+}
+
+// Splits `values` at `mid` into two matrices of the given dimensions and the same strides.
+fn split_mut<T>(
+    values: &mut [T],
+    [nrows1, ncols1]: [usize; 2],
+    [nrows2, ncols2]: [usize; 2],
+    mid: usize,
+    rs: usize,
+    cs: usize,
+) -> (MatMut<'_, T>, MatMut<'_, T>) {
+    let mid = mid.min(values.len());
+    let (first, second) = values.split_at_mut(mid);
+    let first = &mut first[..span(nrows1, ncols1, rs, cs)];
+    let second = &mut second[..span(nrows2, ncols2, rs, cs)];
+    (
+        MatMut::from_parts(nrows1, ncols1, first, rs, cs).unwrap(),
+        MatMut::from_parts(nrows2, ncols2, second, rs, cs).unwrap(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[rustfmt::skip]
+    #[test]
+    fn test_submatrix() {
+        let values = [
+            1, 2, 3, 4,
+            5, 6, 7, 8,
+            9, 10, 11, 12,
+        ];
+        for mat in [MatRef::row_major(3, 4, &values), MatRef::col_major(3, 4, &values)] {
+            let sub = mat.submatrix(1..3, 1..3);
+            assert_eq!([sub.nrows(), sub.ncols()], [2, 2]);
+            for row in 0..2 {
+                for col in 0..2 {
+                    assert_eq!(sub.get(row, col), mat.get(row + 1, col + 1));
+                }
+            }
+            assert_eq!(sub.as_ptr(), &mat.as_slice()[mat.idx(1, 1)] as *const _);
+
+            let row = mat.row(2);
+            assert_eq!([row.nrows(), row.ncols()], [1, 4]);
+            assert!((0..4).all(|j| row.get(0, j) == mat.get(2, j)));
+
+            let col = mat.col(3);
+            assert_eq!([col.nrows(), col.ncols()], [3, 1]);
+            assert!((0..3).all(|i| col.get(i, 0) == mat.get(i, 3)));
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_submatrix_out_of_bounds() {
+        let values = [0; 6];
+        MatRef::row_major(2, 3, &values).submatrix(1..3, 0..1);
+    }
+
+    #[rustfmt::skip]
+    #[test]
+    fn test_split_mut() {
+        let mut values = [0; 12];
+        let mut mat = MatMut::row_major(3, 4, &mut values);
+        let (mut top, mut bottom) = mat.split_at_row_mut(2);
+        assert_eq!([top.nrows(), top.ncols(), bottom.nrows()], [2, 4, 1]);
+        *top.get_mut(1, 3) = 1;
+        *bottom.get_mut(0, 0) = 2;
+        assert_eq!(values, [
+            0, 0, 0, 0,
+            0, 0, 0, 1,
+            2, 0, 0, 0,
+        ]);
+
+        let mut values = [0; 12];
+        let mut mat = MatMut::col_major(3, 4, &mut values);
+        let (mut left, mut right) = mat.split_at_col_mut(1);
+        assert_eq!([left.ncols(), right.nrows(), right.ncols()], [1, 3, 3]);
+        *left.get_mut(2, 0) = 1;
+        *right.get_mut(0, 2) = 2;
+        assert_eq!(values, [
+            0, 0, 1,
+            0, 0, 0,
+            0, 0, 0,
+            2, 0, 0,
+        ]);
+    }
+
+    #[test]
+    fn test_empty_views() {
+        let values = [1, 2, 3, 4, 5, 6];
+        let mat = MatRef::row_major(2, 3, &values);
+        let sub = mat.submatrix(2..2, 1..3);
+        assert_eq!([sub.nrows(), sub.ncols()], [0, 2]);
+        assert!(sub.as_slice().is_empty());
+
+        let mut values = values;
+        let mut mat = MatMut::row_major(2, 3, &mut values);
+        let (top, bottom) = mat.split_at_row_mut(0);
+        assert_eq!([top.nrows(), bottom.nrows()], [0, 2]);
+        let mut row = mat.row_mut(1);
+        let (left, right) = row.split_at_col_mut(3);
+        assert_eq!([left.ncols(), right.ncols()], [3, 0]);
+    }
+
+    #[test]
+    fn test_split_whole() {
+        let mut values = [1, 2, 3];
+        let mut mat = MatMut::col_major(1, 3, &mut values);
+        let (top, bottom) = mat.split_at_row_mut(1);
+        assert_eq!(top.as_slice(), [1, 2, 3]);
+        assert_eq!(bottom.nrows(), 0);
+
+        let mut mat = MatMut::row_major(3, 1, &mut values);
+        let (left, right) = mat.split_at_col_mut(1);
+        assert_eq!(left.as_slice(), [1, 2, 3]);
+        assert_eq!(right.ncols(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "rows interleave in memory")]
+    fn test_split_at_row_mut_col_major() {
+        let mut values = [0; 6];
+        MatMut::col_major(2, 3, &mut values).split_at_row_mut(1);
+    }
+
+    #[test]
+    fn test_try_split_mut_col_major() {
+        let mut values = [0; 16];
+        let mut mat = MatMut::col_major(4, 4, &mut values);
+        assert!(mat.try_split_at_row_mut(2).is_none());
+        let (left, right) = mat.try_split_at_col_mut(2).unwrap();
+        assert_eq!([left.ncols(), right.ncols()], [2, 2]);
+    }
+
+    #[test]
+    fn test_gemm_on_submatrix() {
+        let kernel = kernels::GenericKernel2x2::<i32>::new();
+        let pack_sizes = PackSizes {
+            mc: 2,
+            kc: 2,
+            nc: 2,
+        };
+        let mut buf = [0; 8];
+
+        let a = [1, 2, 3, 4, 5, 6];
+        let a = MatRef::row_major(2, 3, &a);
+        let mut c = [0; 9];
+        let mut c = MatMut::row_major(3, 3, &mut c);
+
+        let mut dst = c.submatrix_mut(1..3, 1..3);
+        let lhs = a.submatrix(0..2, 1..3);
+        let rhs = a.t().submatrix(1..3, 0..2);
+        kernel.gemm(1, lhs, rhs, 0, &mut dst, pack_sizes, &mut buf);
+        assert_eq!(c.as_slice(), [0, 0, 0, 0, 13, 28, 0, 28, 61]);
+    }
+}
@@ -0,0 +1,311 @@
+use crate::gemm::{check_gemm_args, gemm_loops, scale};
+use crate::kernel::Multiply;
+use crate::packing::{block_len, BlocksA, Converter, PanelsB};
+use crate::{DynKernel, Epilogue, GemmError, Kernel, MatMut, MatRef, PackSizes};
+use core::ops::Range;
+use generic_array::{sequence::GenericSequence, GenericArray};
+use num_traits::{One, Zero};
+
+type Product<L, R> = <L as Multiply<R>>::Output;
+
+// c <- alpha a b + beta c, where `a` and `b` are converted to `T` while they are packed,
+// so that the kernel accumulates and writes `c` in `T`.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn try_gemm_mixed<T, K, A, B>(
+    kernel: &K,
+    alpha: T,
+    a: MatRef<A>,
+    b: MatRef<B>,
+    beta: T,
+    c: &mut MatMut<T>,
+    pack_sizes: PackSizes,
+    packing_buf: &mut [T],
+) -> Result<(), GemmError>
+where
+    T: Copy + Zero + One,
+    K: Kernel<Scalar = T> + ?Sized,
+    A: Copy + Into<T>,
+    B: Copy + Into<T>,
+{
+    let [mr, nr] = [K::MR, K::NR];
+    let pack_sizes = check_gemm_args(mr, nr, a, b, c.to_ref(), pack_sizes, packing_buf.len())?;
+
+    let packing_buf = &mut packing_buf[..pack_sizes.buf_len()];
+    let (apack, bpack) = pack_sizes.split_buf(packing_buf);
+    let zero = T::zero();
+    let mut dst_buf = GenericArray::<T, Product<K::Mr, K::Nr>>::generate(|_| zero);
+    let k = a.ncols();
+    let epilogue = None::<&mut dyn Epilogue<T>>;
+    gemm_loops(
+        kernel,
+        alpha,
+        &mut Converter::new(a, apack),
+        &mut Converter::new(b, bpack),
+        beta,
+        c,
+        k,
+        pack_sizes,
+        dst_buf.as_mut_slice(),
+        epilogue,
+    );
+    Ok(())
+}
+
+// c <- alpha a b + beta c for a `c` of another scalar `O`: each tile of alpha a b is accumulated
+// in `T` over all of `k`, then converted to `O` when it is written back to `c`.
+// A tile is written back once, so the blocks of `a` and `b` span all of `k` if a block of `mr`
+// rows and a panel of `nr` columns of that depth fit in the halves of `packing_buf`,
+// otherwise the tiles are computed one at a time, from blocks of the depth that fits.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn try_gemm_mixed_into<T, K, A, B, O>(
+    kernel: &K,
+    alpha: T,
+    a: MatRef<A>,
+    b: MatRef<B>,
+    beta: O,
+    c: &mut MatMut<O>,
+    pack_sizes: PackSizes,
+    packing_buf: &mut [T],
+) -> Result<(), GemmError>
+where
+    T: Copy + Zero + One + Into<O>,
+    K: Kernel<Scalar = T> + ?Sized,
+    A: Copy + Into<T>,
+    B: Copy + Into<T>,
+    O: Copy + Zero + One,
+{
+    let [mr, nr] = [K::MR, K::NR];
+    let pack_sizes = check_gemm_args(mr, nr, a, b, c.to_ref(), pack_sizes, packing_buf.len())?;
+    let [m, k, n] = [c.nrows(), a.ncols(), c.ncols()];
+    if m == 0 || n == 0 {
+        return Ok(());
+    }
+    if k == 0 {
+        // alpha a b is an empty sum, as in reference BLAS
+        scale(beta, c);
+        return Ok(());
+    }
+
+    let packing_buf = &mut packing_buf[..pack_sizes.buf_len()];
+    let (apack, bpack) = pack_sizes.split_buf(packing_buf);
+    // at least `pack_sizes.kc`, since `mc >= mr` and `nc >= nr`
+    let kc = k.min(apack.len() / mr).min(bpack.len() / nr);
+    let [mc, nc] = if kc == k {
+        [apack.len() / (k * mr) * mr, bpack.len() / (k * nr) * nr]
+    } else {
+        // a single tile, whose sum is kept in `dst_buf` across the blocks
+        [mr, nr]
+    };
+
+    let mut a_blocks = Converter::new(a, apack);
+    let mut b_panels = Converter::new(b, bpack);
+    let zero = T::zero();
+    let mut dst_buf = GenericArray::<T, Product<K::Mr, K::Nr>>::generate(|_| zero);
+    let dst_buf = dst_buf.as_mut_slice();
+
+    for jc in (0..n).step_by(nc) {
+        let nc = block_len(nc, nr, n - jc);
+        for ic in (0..m).step_by(mc) {
+            let mc = block_len(mc, mr, m - ic);
+            for pc in (0..k).step_by(kc) {
+                let depth = pc..k.min(pc + kc);
+                let kc = depth.len();
+                let bpack = b_panels.panel_b(nr, depth.clone(), jc..jc + nc);
+                let apack = a_blocks.block_a(mr, ic..ic + mc, depth.clone());
+
+                for (l2, jr) in (0..nc).step_by(nr).enumerate() {
+                    let rhs = &bpack[kc * nr * l2..kc * nr * (l2 + 1)];
+                    for (l1, ir) in (0..mc).step_by(mr).enumerate() {
+                        let lhs = &apack[mr * kc * l1..mr * kc * (l1 + 1)];
+                        if pc == 0 {
+                            kernel.dyn_microkernel_overwrite(alpha, lhs, rhs, dst_buf);
+                        } else {
+                            kernel.dyn_microkernel(alpha, lhs, rhs, T::one(), dst_buf);
+                        }
+                        if depth.end == k {
+                            let [rows, cols] = [ic + ir..ic + ir + mr, jc + jr..jc + jr + nr];
+                            convert_to_c(dst_buf, beta, c, rows, cols);
+                        }
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+// c[rows, cols] <- tile + beta c[rows, cols] for the col-major `tile` of `rows.len()` rows,
+// converted to `O`. Rows and columns outside of `c` are ignored,
+// and c is not read if beta is zero, as in reference BLAS.
+fn convert_to_c<T, O>(
+    tile: &[T],
+    beta: O,
+    c: &mut MatMut<O>,
+    rows: Range<usize>,
+    cols: Range<usize>,
+) where
+    T: Copy + Into<O>,
+    O: Copy + Zero + One,
+{
+    let nrows = c.nrows().min(rows.end) - rows.start;
+    let ncols = c.ncols().min(cols.end) - cols.start;
+    for j in 0..ncols {
+        for i in 0..nrows {
+            let value = tile[i + j * rows.len()].into();
+            let z = c.get_mut(rows.start + i, cols.start + j);
+            *z = if beta.is_zero() {
+                value
+            } else {
+                value + beta * *z
+            };
+        }
+    }
+}
+
+#[cfg(not(miri))]
+#[cfg(test)]
+mod proptests {
+    use crate::{
+        kernels::GenericKernel4x4,
+        mat::base::MatBase,
+        std_prelude::*,
+        utils::{arb_matrix_triple_with, naive_gemm},
+        GemmError, Kernel, MatMut, MatRef, PackSizes,
+    };
+    use half::{bf16, f16};
+    use proptest::prelude::*;
+
+    // Converts the values of `mat`, keeping its layout.
+    fn convert<S, T>(mat: &MatBase<Vec<S>, S>, f: impl Fn(S) -> T) -> MatBase<Vec<T>, T>
+    where
+        S: Copy,
+    {
+        let values = mat.as_slice().iter().copied().map(f).collect();
+        let [row_stride, col_stride] = [mat.row_stride(), mat.col_stride()];
+        MatBase::from_parts(mat.nrows(), mat.ncols(), values, row_stride, col_stride).unwrap()
+    }
+
+    proptest! {
+        #[test]
+        fn proptest_gemm_mixed_i16_i32(
+            // the products overflow i16, but not i32 for these dimensions and scalars
+            [a, b, c] in arb_matrix_triple_with(1..30, 1..30, 1..30, -1000i16..1000),
+            alpha in -10i32..10,
+            beta in -10i32..10,
+            [mc, kc, nc] in [4..20usize, 1..20, 4..20],
+        ) {
+            let c = convert(&c, i32::from);
+
+            let mut expect = c.clone();
+            let [a_i32, b_i32] = [convert(&a, i32::from), convert(&b, i32::from)];
+            naive_gemm(alpha, a_i32.to_ref(), b_i32.to_ref(), beta, &mut expect.to_mut());
+
+            let kernel = GenericKernel4x4::<i32>::new();
+            let pack_sizes = PackSizes { mc, kc, nc };
+            let mut packing_buf = vec![0; pack_sizes.buf_len()];
+            let mut actual = c;
+            kernel.gemm_mixed(alpha, a.to_ref(), b.to_ref(), beta, &mut actual.to_mut(), pack_sizes, &mut packing_buf);
+            prop_assert_eq!(expect.as_slice(), actual.as_slice());
+        }
+
+        #[test]
+        fn proptest_gemm_mixed_f16_bf16(
+            [a, b, c] in arb_matrix_triple_with(1..20, 1..40, 1..20, -64i8..64),
+            [mc, kc, nc] in [4..20usize, 1..20, 4..20],
+        ) {
+            // small integers are exact in f16, bf16 and f32, and so are the sums of their products
+            let a = convert(&a, |x| f16::from_f32(x.into()));
+            let b = convert(&b, |x| bf16::from_f32(x.into()));
+            let c = convert(&c, f32::from);
+
+            let mut expect = c.clone();
+            let [a_f32, b_f32] = [convert(&a, f32::from), convert(&b, f32::from)];
+            naive_gemm(2.0, a_f32.to_ref(), b_f32.to_ref(), -1.0, &mut expect.to_mut());
+
+            let kernel = GenericKernel4x4::<f32>::new();
+            let pack_sizes = PackSizes { mc, kc, nc };
+            let mut packing_buf = vec![0.0; pack_sizes.buf_len()];
+            let mut actual = c;
+            kernel.gemm_mixed(2.0, a.to_ref(), b.to_ref(), -1.0, &mut actual.to_mut(), pack_sizes, &mut packing_buf);
+            prop_assert_eq!(expect.as_slice(), actual.as_slice());
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn proptest_gemm_mixed_into_i64(
+            [a, b, c] in arb_matrix_triple_with(1..30, 1..30, 1..30, -1000i16..1000),
+            alpha in -10i32..10,
+            beta in -10i64..10,
+            // blocks of all of k, or of single tiles for the larger k
+            [mc, kc, nc] in [4..20usize, 1..20, 4..20],
+        ) {
+            let c = convert(&c, |x| i64::from(x) << 32);
+
+            let mut expect = c.clone();
+            let [a_i64, b_i64] = [convert(&a, i64::from), convert(&b, i64::from)];
+            naive_gemm(alpha.into(), a_i64.to_ref(), b_i64.to_ref(), beta, &mut expect.to_mut());
+
+            let kernel = GenericKernel4x4::<i32>::new();
+            let pack_sizes = PackSizes { mc, kc, nc };
+            let mut packing_buf = vec![0; pack_sizes.buf_len()];
+            let mut actual = c;
+            kernel.gemm_mixed_into(alpha, a.to_ref(), b.to_ref(), beta, &mut actual.to_mut(), pack_sizes, &mut packing_buf);
+            prop_assert_eq!(expect.as_slice(), actual.as_slice());
+        }
+    }
+
+    #[test]
+    fn test_gemm_mixed_into_ignores_c_if_beta_is_zero() {
+        let a = [f16::ONE; 6];
+        let a = MatRef::row_major(2, 3, &a);
+        let b = MatRef::row_major(3, 1, &a.as_slice()[..3]);
+
+        let kernel = GenericKernel4x4::<f32>::new();
+        let pack_sizes = PackSizes {
+            mc: 4,
+            kc: 1,
+            nc: 4,
+        };
+        let mut packing_buf = vec![0.0; pack_sizes.buf_len()];
+        let mut c = [f64::NAN; 2];
+        let mut c = MatMut::row_major(2, 1, &mut c);
+        kernel.gemm_mixed_into(2.0, a, b, 0.0, &mut c, pack_sizes, &mut packing_buf);
+        assert_eq!(c.as_slice(), [6.0, 6.0]);
+    }
+
+    #[test]
+    fn test_gemm_mixed_accumulates_in_f32() {
+        let k = 4096;
+        let a = vec![f16::from_f32(0.1); k];
+        let b = vec![f16::ONE; k];
+        let a = MatRef::row_major(1, k, &a);
+        let b = MatRef::row_major(k, 1, &b);
+
+        let kernel = GenericKernel4x4::<f32>::new();
+        let pack_sizes = PackSizes {
+            mc: 4,
+            kc: 256,
+            nc: 4,
+        };
+        let mut packing_buf = vec![0.0; pack_sizes.buf_len()];
+        let mut c = [0.0];
+        let mut c = MatMut::row_major(1, 1, &mut c);
+        kernel.gemm_mixed(1.0, a, b, 0.0, &mut c, pack_sizes, &mut packing_buf);
+
+        let expect = k as f32 * f16::from_f32(0.1).to_f32();
+        assert!((c.as_slice()[0] - expect).abs() <= 1e-5 * expect);
+
+        let mut c = [0.0; 4];
+        let mut c = MatMut::row_major(2, 2, &mut c);
+        let result = kernel.try_gemm_mixed(1.0, a, b, 0.0, &mut c, pack_sizes, &mut packing_buf);
+        assert_eq!(
+            result,
+            Err(GemmError::DimMismatch {
+                a: [1, k],
+                b: [k, 1],
+                c: [2, 2]
+            })
+        );
+    }
+}
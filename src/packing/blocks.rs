@@ -0,0 +1,189 @@
+use super::{pack_a, pack_a_with, pack_b, pack_b_with};
+use crate::MatRef;
+use core::ops::Range;
+use num_traits::Zero;
+
+// Source of the packed blocks of `a` read by `gemm_loops`.
+pub(crate) trait BlocksA<T> {
+    // Returns a[rows, cols] packed by `pack_a` for a kernel with `mr` rows,
+    // where `rows.len()` is a multiple of `mr` and rows outside of `a` are zeros.
+    fn block_a(&mut self, mr: usize, rows: Range<usize>, cols: Range<usize>) -> &[T];
+}
+
+// Source of the packed panels of `b` read by `gemm_loops`.
+pub(crate) trait PanelsB<T> {
+    // Returns b[rows, cols] packed by `pack_b` for a kernel with `nr` columns,
+    // where `cols.len()` is a multiple of `nr` and columns outside of `b` are zeros.
+    fn panel_b(&mut self, nr: usize, rows: Range<usize>, cols: Range<usize>) -> &[T];
+}
+
+// Packs the blocks of a matrix on demand into the caller's packing buffer,
+// whose length depends on the runtime `kc` and so cannot be a stack array.
+// A matrix that already has the layout of a single packed panel is read in place.
+pub(crate) struct Packer<'a, T> {
+    mat: MatRef<'a, T>,
+    buf: &'a mut [T],
+}
+
+impl<'a, T> Packer<'a, T> {
+    pub(crate) fn new(mat: MatRef<'a, T>, buf: &'a mut [T]) -> Self {
+        Self { mat, buf }
+    }
+}
+
+impl<T> BlocksA<T> for Packer<'_, T>
+where
+    T: Copy + Zero,
+{
+    #[inline]
+    fn block_a(&mut self, mr: usize, rows: Range<usize>, cols: Range<usize>) -> &[T] {
+        let a = self.mat;
+        let k = a.ncols();
+        // a single col-major panel of `mr` rows
+        if a.nrows() == mr && a.row_stride() == 1 && (k == 1 || a.col_stride() == mr) {
+            debug_assert_eq!(rows, 0..mr);
+            return &self.mat.as_slice()[cols.start * mr..cols.end * mr];
+        }
+        let apack = &mut self.buf[..rows.len() * cols.len()];
+        pack_a(mr, apack, a, rows, cols);
+        apack
+    }
+}
+
+impl<T> PanelsB<T> for Packer<'_, T>
+where
+    T: Copy + Zero,
+{
+    #[inline]
+    fn panel_b(&mut self, nr: usize, rows: Range<usize>, cols: Range<usize>) -> &[T] {
+        let b = self.mat;
+        let k = b.nrows();
+        // a single row-major panel of `nr` columns
+        if b.ncols() == nr && b.col_stride() == 1 && (k == 1 || b.row_stride() == nr) {
+            debug_assert_eq!(cols, 0..nr);
+            return &self.mat.as_slice()[rows.start * nr..rows.end * nr];
+        }
+        let bpack = &mut self.buf[..rows.len() * cols.len()];
+        pack_b(nr, bpack, b, rows, cols);
+        bpack
+    }
+}
+
+// A panel of `b` packed beforehand, shared by the workers of the parallel drivers.
+pub(crate) struct Panel<'a, T>(pub(crate) &'a [T]);
+
+impl<T> PanelsB<T> for Panel<'_, T> {
+    #[inline]
+    fn panel_b(&mut self, _nr: usize, rows: Range<usize>, cols: Range<usize>) -> &[T] {
+        debug_assert_eq!(self.0.len(), rows.len() * cols.len());
+        self.0
+    }
+}
+
+// Packs the blocks of a matrix of `S` on demand, converting its values to `T`,
+// for `gemm_mixed`.
+pub(crate) struct Converter<'a, S, T> {
+    mat: MatRef<'a, S>,
+    buf: &'a mut [T],
+}
+
+impl<'a, S, T> Converter<'a, S, T> {
+    pub(crate) fn new(mat: MatRef<'a, S>, buf: &'a mut [T]) -> Self {
+        Self { mat, buf }
+    }
+}
+
+impl<S, T> BlocksA<T> for Converter<'_, S, T>
+where
+    S: Copy + Into<T>,
+    T: Copy + Zero,
+{
+    #[inline]
+    fn block_a(&mut self, mr: usize, rows: Range<usize>, cols: Range<usize>) -> &[T] {
+        let apack = &mut self.buf[..rows.len() * cols.len()];
+        pack_a_with(mr, apack, self.mat, rows, cols, convert_lane);
+        apack
+    }
+}
+
+impl<S, T> PanelsB<T> for Converter<'_, S, T>
+where
+    S: Copy + Into<T>,
+    T: Copy + Zero,
+{
+    #[inline]
+    fn panel_b(&mut self, nr: usize, rows: Range<usize>, cols: Range<usize>) -> &[T] {
+        let bpack = &mut self.buf[..rows.len() * cols.len()];
+        pack_b_with(nr, bpack, self.mat, rows, cols, convert_lane);
+        bpack
+    }
+}
+
+#[inline]
+fn convert_lane<S, T>(dst: &mut [T], src: &[S])
+where
+    S: Copy + Into<T>,
+{
+    dst.iter_mut()
+        .zip(src)
+        .for_each(|(dst, &src)| *dst = src.into());
+}
+
+#[cfg(not(miri))]
+#[cfg(test)]
+mod proptests {
+    use crate::{
+        as_mut,
+        kernels::GenericKernel4x4,
+        std_prelude::*,
+        utils::{arb_matrix_triple_with, naive_gemm},
+        Kernel, MatMut, MatRef, PackSizes,
+    };
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn proptest_small_gemm(
+            [a, b, c] in arb_matrix_triple_with(1..=8, 1..40, 1..=8, -10i32..10),
+            alpha in -10i32..10,
+            beta in -10i32..10,
+            kc in 1..20usize,
+        ) {
+            let mut expect = c.clone();
+            naive_gemm(alpha, a.to_ref(), b.to_ref(), beta, as_mut!(expect));
+
+            let kernel = GenericKernel4x4::new();
+            let pack_sizes = PackSizes { mc: 8, kc, nc: 8 };
+            let mut packing_buf = vec![-1; pack_sizes.buf_len()];
+            let mut actual = c;
+            kernel.gemm(alpha, a.to_ref(), b.to_ref(), beta, as_mut!(actual), pack_sizes, &mut packing_buf);
+            prop_assert_eq!(expect.as_slice(), actual.as_slice());
+        }
+
+        #[test]
+        fn proptest_small_gemm_direct(
+            k in 1..40usize,
+            alpha in -10i32..10,
+            beta in -10i32..10,
+            kc in 1..20usize,
+            seed in any::<i32>(),
+        ) {
+            let values = |len: usize| (0..len).map(|x| (x as i32 ^ seed) % 10).collect::<Vec<_>>();
+            let [a, b, c] = [values(4 * k), values(k * 4), values(4 * 4)];
+            // the layouts of packed panels
+            let a = MatRef::col_major(4, k, &a);
+            let b = MatRef::row_major(k, 4, &b);
+
+            let mut expect = c.clone();
+            naive_gemm(alpha, a, b, beta, &mut MatMut::row_major(4, 4, &mut expect));
+
+            let kernel = GenericKernel4x4::new();
+            let pack_sizes = PackSizes { mc: 4, kc, nc: 4 };
+            let mut packing_buf = vec![-1; pack_sizes.buf_len()];
+            let mut actual = c;
+            let mut c = MatMut::row_major(4, 4, &mut actual);
+            kernel.gemm(alpha, a, b, beta, &mut c, pack_sizes, &mut packing_buf);
+            prop_assert_eq!(expect, actual);
+        }
+    }
+}
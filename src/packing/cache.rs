@@ -0,0 +1,90 @@
+/// Sizes in bytes of the data caches of a core, used by [`PackSizes::for_kernel`].
+///
+/// A size of zero means that the cache level is not present.
+///
+/// [`PackSizes::for_kernel`]: crate::PackSizes::for_kernel
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CacheInfo {
+    pub l1: usize,
+    pub l2: usize,
+    pub l3: usize,
+}
+
+impl CacheInfo {
+    /// Reads the sizes of the data caches of `cpu0` from
+    /// `/sys/devices/system/cpu/cpu0/cache/index*` on Linux.
+    /// Returns `None` if the L1 or L2 size could not be read.
+    #[cfg(feature = "std")]
+    pub fn detect() -> Option<Self> {
+        use std::{fs, path::Path};
+
+        let read = |dir: &Path, name: &str| fs::read_to_string(dir.join(name)).ok();
+
+        let mut info = Self {
+            l1: 0,
+            l2: 0,
+            l3: 0,
+        };
+        for entry in fs::read_dir("/sys/devices/system/cpu/cpu0/cache").ok()? {
+            let dir = entry.ok()?.path();
+            let is_index = dir
+                .file_name()
+                .and_then(|name| name.to_str())
+                .map_or(false, |name| name.starts_with("index"));
+            if !is_index {
+                continue;
+            }
+            if read(&dir, "type").map_or(true, |ty| ty.trim() == "Instruction") {
+                continue;
+            }
+            let Some(size) = read(&dir, "size").and_then(|size| parse_size(&size)) else {
+                continue;
+            };
+            match read(&dir, "level").as_deref().map(str::trim) {
+                Some("1") => info.l1 = size,
+                Some("2") => info.l2 = size,
+                Some("3") => info.l3 = size,
+                _ => {}
+            }
+        }
+        if info.l1 == 0 || info.l2 == 0 {
+            return None;
+        }
+        Some(info)
+    }
+}
+
+// Parses a cache size of sysfs, such as "48K", "2048K" or "32M".
+#[cfg(any(test, feature = "std"))]
+pub(crate) fn parse_size(s: &str) -> Option<usize> {
+    let s = s.trim();
+    let (digits, unit) = match s.char_indices().find(|(_, ch)| !ch.is_ascii_digit()) {
+        Some((at, _)) => s.split_at(at),
+        None => (s, ""),
+    };
+    let size: usize = digits.parse().ok()?;
+    let unit = match unit {
+        "" => 1,
+        "K" => 1 << 10,
+        "M" => 1 << 20,
+        "G" => 1 << 30,
+        _ => return None,
+    };
+    size.checked_mul(unit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(parse_size("48K\n"), Some(48 * 1024));
+        assert_eq!(parse_size("32M"), Some(32 * 1024 * 1024));
+        assert_eq!(parse_size("512"), Some(512));
+        assert_eq!(parse_size("1G"), Some(1 << 30));
+        assert_eq!(parse_size("K"), None);
+        assert_eq!(parse_size("12KB"), None);
+        assert_eq!(parse_size(""), None);
+    }
+}
@@ -1,10 +1,15 @@
+mod blocks;
+mod cache;
 mod pack_a;
 mod pack_b;
 mod registers;
 mod sizes;
 
-pub(crate) use pack_a::pack_a;
-pub(crate) use pack_b::pack_b;
+pub(crate) use blocks::{BlocksA, Converter, Packer, Panel, PanelsB};
+pub(crate) use pack_a::{pack_a, pack_a_with};
+pub(crate) use pack_b::{pack_b, pack_b_with};
 pub(crate) use registers::{registers_from_c, registers_to_c};
+pub(crate) use sizes::block_len;
 
+pub use cache::CacheInfo;
 pub use sizes::PackSizes;
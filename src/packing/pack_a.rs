@@ -13,6 +13,24 @@ pub(crate) fn pack_a<T>(
     cols: Range<usize>,
 ) where
     T: Zero + Copy,
+{
+    pack_a_with(mr, apack, a, rows, cols, <[T]>::copy_from_slice);
+}
+
+// `pack_a` for an `a` of another scalar, whose values are converted to `T`.
+// `copy_lane` copies a contiguous lane of `a`, the strided ones are converted by `Into`.
+#[inline]
+pub(crate) fn pack_a_with<S, T, F>(
+    mr: usize,
+    apack: &mut [T],
+    a: MatRef<S>,
+    rows: Range<usize>,
+    cols: Range<usize>,
+    copy_lane: F,
+) where
+    S: Copy + Into<T>,
+    T: Zero + Copy,
+    F: Fn(&mut [T], &[S]),
 {
     let mc = rows.len();
     let kc = cols.len();
@@ -40,20 +58,26 @@ pub(crate) fn pack_a<T>(
         debug_assert!(block_rows.end <= a.nrows());
         debug_assert_eq!(block_rows.len(), mr);
 
+        if stride != 1 && a.col_stride() == 1 {
+            pack_row_lanes(mr, &mut it[..mr * kc], a, block_rows, cols.clone());
+            it = &mut it[mr * kc..];
+            continue;
+        }
+
         for col in cols.clone() {
             debug_assert!(col < a.ncols());
             let idx = a.idx(block_rows.start, col);
 
             if stride == 1 {
                 let lane = &a.as_slice()[idx..idx + mr];
-                it[..mr].copy_from_slice(lane);
+                copy_lane(&mut it[..mr], lane);
             } else {
                 let lane = a.as_slice()[idx..].iter().step_by(stride).take(mr);
                 debug_assert_eq!(lane.len(), mr);
                 let zip = lane.zip(&mut it[..mr]);
                 #[cfg(not(kani))]
                 zip.for_each(|(&src, dst)| {
-                    *dst = src;
+                    *dst = src.into();
                 });
             }
             it = &mut it[mr..];
@@ -68,25 +92,34 @@ pub(crate) fn pack_a<T>(
         debug_assert!(block_rows.start < block_rows.end);
         debug_assert_eq!(block_rows.len(), remains);
 
-        for col in cols.clone() {
-            debug_assert!(col < a.ncols());
-            let idx = a.idx(block_rows.start, col);
-
-            if stride == 1 {
-                let lane = &a.as_slice()[idx..idx + remains];
-                it[..remains].copy_from_slice(lane);
-            } else {
-                let lane = a.as_slice()[idx..].iter().step_by(stride).take(remains);
-                debug_assert_eq!(lane.len(), remains);
-                let zip = lane.zip(&mut it[..remains]);
+        if stride != 1 && a.col_stride() == 1 {
+            pack_row_lanes(mr, &mut it[..mr * kc], a, block_rows, cols.clone());
+            #[cfg(not(kani))]
+            it[..mr * kc]
+                .chunks_exact_mut(mr)
+                .for_each(|lane| lane[remains..].fill(T::zero()));
+            it = &mut it[mr * kc..];
+        } else {
+            for col in cols.clone() {
+                debug_assert!(col < a.ncols());
+                let idx = a.idx(block_rows.start, col);
+
+                if stride == 1 {
+                    let lane = &a.as_slice()[idx..idx + remains];
+                    copy_lane(&mut it[..remains], lane);
+                } else {
+                    let lane = a.as_slice()[idx..].iter().step_by(stride).take(remains);
+                    debug_assert_eq!(lane.len(), remains);
+                    let zip = lane.zip(&mut it[..remains]);
+                    #[cfg(not(kani))]
+                    zip.for_each(|(&src, dst)| {
+                        *dst = src.into();
+                    });
+                }
                 #[cfg(not(kani))]
-                zip.for_each(|(&src, dst)| {
-                    *dst = src;
-                });
+                it[remains..mr].fill(T::zero());
+                it = &mut it[mr..];
             }
-            #[cfg(not(kani))]
-            it[remains..mr].fill(T::zero());
-            it = &mut it[mr..];
         }
     }
 
@@ -94,6 +127,37 @@ pub(crate) fn pack_a<T>(
     it.fill(T::zero());
 }
 
+// Copy a[rows, cols] of a row-major `a` into a col-major block of height `mr`,
+// reading each row as a contiguous lane.
+#[inline]
+fn pack_row_lanes<S, T>(
+    mr: usize,
+    block: &mut [T],
+    a: MatRef<S>,
+    rows: Range<usize>,
+    cols: Range<usize>,
+) where
+    S: Copy + Into<T>,
+{
+    debug_assert_eq!(a.col_stride(), 1);
+    debug_assert!(rows.len() <= mr);
+    debug_assert_eq!(block.len(), mr * cols.len());
+
+    let kc = cols.len();
+    if kc == 0 {
+        return;
+    }
+    for (i, row) in rows.enumerate() {
+        let idx = a.idx(row, cols.start);
+        let lane = &a.as_slice()[idx..idx + kc];
+        let zip = lane.iter().zip(block[i..].iter_mut().step_by(mr));
+        #[cfg(not(kani))]
+        zip.for_each(|(&src, dst)| {
+            *dst = src.into();
+        });
+    }
+}
+
 #[cfg(test)]
 mod reference {
     use super::*;
@@ -1,6 +1,7 @@
+use super::CacheInfo;
 use crate::Kernel;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct PackSizes {
     pub mc: usize,
     pub kc: usize,
@@ -8,6 +9,34 @@ pub struct PackSizes {
 }
 
 impl PackSizes {
+    /// Derives the block sizes for `K` from the sizes of the caches,
+    /// following the heuristics of BLIS:
+    ///
+    /// 1. `kc`: the `mr x kc` and `kc x nr` micropanels take about half of L1.
+    /// 2. `mc`: the `mc x kc` block of `a` takes about half of L2.
+    /// 3. `nc`: the `kc x nc` panel of `b` takes about half of L3, or of L2 without L3.
+    ///
+    /// `mc` and `nc` are multiples of `mr` and `nr`, and never less than them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use microgemm::{kernels::GenericKernel8x8, CacheInfo, PackSizes};
+    ///
+    /// let cache = CacheInfo { l1: 32 << 10, l2: 1 << 20, l3: 32 << 20 };
+    /// let pack_sizes = PackSizes::for_kernel::<GenericKernel8x8<f32>>(cache);
+    /// assert_eq!(pack_sizes, PackSizes { mc: 512, kc: 256, nc: 16384 });
+    /// ```
+    pub fn for_kernel<K: Kernel + ?Sized>(cache: CacheInfo) -> Self {
+        let size = core::mem::size_of::<K::Scalar>().max(1);
+        let [mr, nr] = [K::MR, K::NR];
+        let l3 = if cache.l3 > 0 { cache.l3 } else { cache.l2 };
+
+        let kc = (cache.l1 / 2 / size / (mr + nr)).max(1);
+        let mc = (cache.l2 / 2 / size / kc / mr).max(1) * mr;
+        let nc = (l3 / 2 / size / kc / nr).max(1) * nr;
+        Self { mc, kc, nc }
+    }
     pub const fn buf_len(self) -> usize {
         self.mc * self.kc + self.kc * self.nc
     }
@@ -16,12 +45,122 @@ impl PackSizes {
         let bpack_len = self.kc.checked_mul(self.nc)?;
         apack_len.checked_add(bpack_len)
     }
-    pub(crate) fn clamped<T, K>(self, _: &K) -> Self
-    where
-        K: Kernel<Scalar = T> + ?Sized,
-    {
-        let mr = K::MR;
-        let nr = K::NR;
+    /// Length of the workspace for [`Kernel::gemm_with_executor`] with `workers` workers:
+    /// a `kc x nc` panel of `b`, shared by the workers, and an `mc x kc` block of `a` per worker.
+    ///
+    /// [`Kernel::gemm_with_executor`]: crate::Kernel::gemm_with_executor
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use microgemm::PackSizes;
+    ///
+    /// let pack_sizes = PackSizes { mc: 4, kc: 3, nc: 2 };
+    /// assert_eq!(pack_sizes.par_buf_len(1), pack_sizes.buf_len());
+    /// assert_eq!(pack_sizes.par_buf_len(3), 3 * 2 + 3 * (4 * 3));
+    /// ```
+    pub const fn par_buf_len(self, workers: usize) -> usize {
+        self.kc * self.nc + workers * self.mc * self.kc
+    }
+    pub(crate) fn checked_par_buf_len(self, workers: usize) -> Option<usize> {
+        let apacks_len = workers.checked_mul(self.mc.checked_mul(self.kc)?)?;
+        self.kc.checked_mul(self.nc)?.checked_add(apacks_len)
+    }
+    /// Length of the packing buffer of [`Kernel::gemm_batched`] and [`Kernel::gemm_strided_batched`]
+    /// that lets a `k x n` matrix `b`, shared by the whole batch, be packed only once:
+    /// an `mc x kc` block of `a` and the whole packed `b`, see [`packed_b_len`](Self::packed_b_len),
+    /// or a `kc x nc` panel of `b` if it is larger.
+    ///
+    /// [`Kernel::gemm_batched`]: crate::Kernel::gemm_batched
+    /// [`Kernel::gemm_strided_batched`]: crate::Kernel::gemm_strided_batched
+    ///
+    /// # Panics
+    /// If `self.nc < kernel.nr()`, `self.kc` is zero or the length overflows.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use microgemm::{kernels::GenericKernel4x4, PackSizes};
+    ///
+    /// let kernel = GenericKernel4x4::<f32>::new();
+    /// let pack_sizes = PackSizes { mc: 4, kc: 3, nc: 8 };
+    /// assert_eq!(pack_sizes.batched_buf_len(&kernel, 5, 10), 4 * 3 + 5 * 12);
+    /// assert_eq!(pack_sizes.batched_buf_len(&kernel, 2, 3), pack_sizes.buf_len());
+    /// ```
+    pub fn batched_buf_len<K: Kernel + ?Sized>(self, kernel: &K, k: usize, n: usize) -> usize {
+        let nr = kernel.nr();
+        assert!(nr > 0);
+        assert!(nr <= self.nc, "nc is less than nr");
+        assert!(self.kc > 0, "kc is zero");
+        self.checked_batched_buf_len(nr, k, n)
+            .expect("packing buffer length overflows usize")
+    }
+    pub(crate) fn checked_batched_buf_len(self, nr: usize, k: usize, n: usize) -> Option<usize> {
+        let bpack_len = self.checked_packed_b_len(nr, k, n)?;
+        let bpack_len = bpack_len.max(self.kc.checked_mul(self.nc)?);
+        self.mc.checked_mul(self.kc)?.checked_add(bpack_len)
+    }
+    /// Length of the buffer of [`PackedB::pack_into`] for a `k x n` matrix `b`:
+    /// `k` times `n` padded to a multiple of `kernel.nr()`.
+    ///
+    /// [`PackedB::pack_into`]: crate::PackedB::pack_into
+    ///
+    /// # Panics
+    /// If `self.nc < kernel.nr()`, `self.kc` is zero or the length overflows.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use microgemm::{kernels::GenericKernel4x4, PackSizes};
+    ///
+    /// let kernel = GenericKernel4x4::<f32>::new();
+    /// let pack_sizes = PackSizes { mc: 4, kc: 3, nc: 8 };
+    /// assert_eq!(pack_sizes.packed_b_len(&kernel, 5, 10), 5 * 12);
+    /// ```
+    pub fn packed_b_len<K: Kernel + ?Sized>(self, kernel: &K, k: usize, n: usize) -> usize {
+        let nr = kernel.nr();
+        assert!(nr > 0);
+        assert!(nr <= self.nc, "nc is less than nr");
+        assert!(self.kc > 0, "kc is zero");
+        self.checked_packed_b_len(nr, k, n)
+            .expect("packed length overflows usize")
+    }
+    pub(crate) fn checked_packed_b_len(self, nr: usize, k: usize, n: usize) -> Option<usize> {
+        let nc = self.nc - self.nc % nr;
+        let padded_n = (n / nc) * nc + block_len(nc, nr, n % nc);
+        padded_n.checked_mul(k)
+    }
+    /// Length of the buffer of [`PackedA::pack_into`] for an `m x k` matrix `a`:
+    /// `m` padded to a multiple of `kernel.mr()` times `k`.
+    ///
+    /// [`PackedA::pack_into`]: crate::PackedA::pack_into
+    ///
+    /// # Panics
+    /// If `self.mc < kernel.mr()`, `self.kc` is zero or the length overflows.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use microgemm::{kernels::GenericKernel4x4, PackSizes};
+    ///
+    /// let kernel = GenericKernel4x4::<f32>::new();
+    /// let pack_sizes = PackSizes { mc: 8, kc: 3, nc: 4 };
+    /// assert_eq!(pack_sizes.packed_a_len(&kernel, 10, 5), 12 * 5);
+    /// ```
+    pub fn packed_a_len<K: Kernel + ?Sized>(self, kernel: &K, m: usize, k: usize) -> usize {
+        let mr = kernel.mr();
+        assert!(mr > 0);
+        assert!(mr <= self.mc, "mc is less than mr");
+        assert!(self.kc > 0, "kc is zero");
+        self.checked_packed_a_len(mr, m, k)
+            .expect("packed length overflows usize")
+    }
+    pub(crate) fn checked_packed_a_len(self, mr: usize, m: usize, k: usize) -> Option<usize> {
+        let mc = self.mc - self.mc % mr;
+        let padded_m = (m / mc) * mc + block_len(mc, mr, m % mc);
+        padded_m.checked_mul(k)
+    }
+    pub(crate) fn clamped(self, mr: usize, nr: usize) -> Self {
         assert!(mr > 0);
         assert!(nr > 0);
         assert!(mr <= self.mc);
@@ -37,11 +176,23 @@ impl PackSizes {
         let (apack, bpack) = buf.split_at_mut(self.mc * self.kc);
         (apack, bpack)
     }
+    // Splits the workspace of `par_buf_len(workers)` into the shared buffer of `b`
+    // and the buffers of `a` of the workers.
+    pub(crate) fn split_par_buf<T>(self, workers: usize, buf: &mut [T]) -> (&mut [T], &mut [T]) {
+        assert_eq!(buf.len(), self.checked_par_buf_len(workers).unwrap());
+        buf.split_at_mut(self.kc * self.nc)
+    }
+}
+
+// Number of rows or columns of a block with `len` of them left, padded to a multiple of `r`.
+pub(crate) fn block_len(c: usize, r: usize, len: usize) -> usize {
+    c.min((len + r - 1) / r * r)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::std_prelude::*;
 
     #[test]
     fn test_pack_sizes_bug_len() {
@@ -49,4 +200,52 @@ mod tests {
         let pack_sizes = PackSizes { mc, kc, nc };
         assert_eq!(pack_sizes.buf_len(), pack_sizes.checked_buf_len().unwrap());
     }
+
+    #[test]
+    fn test_for_kernel() {
+        use crate::kernels::{GenericKernel4x4, GenericKernel8x8};
+
+        let cache = CacheInfo {
+            l1: 48 << 10,
+            l2: 2 << 20,
+            l3: 0,
+        };
+        let pack_sizes = PackSizes::for_kernel::<GenericKernel4x4<f64>>(cache);
+        assert_eq!(
+            pack_sizes,
+            PackSizes {
+                mc: 340,
+                kc: 384,
+                nc: 340
+            }
+        );
+
+        let cache = CacheInfo {
+            l1: 0,
+            l2: 0,
+            l3: 0,
+        };
+        let pack_sizes = PackSizes::for_kernel::<GenericKernel8x8<f32>>(cache);
+        assert_eq!(
+            pack_sizes,
+            PackSizes {
+                mc: 8,
+                kc: 1,
+                nc: 8
+            }
+        );
+    }
+
+    #[test]
+    fn test_split_par_buf() {
+        let pack_sizes = PackSizes {
+            mc: 4,
+            kc: 3,
+            nc: 2,
+        };
+        let mut buf = vec![0; pack_sizes.par_buf_len(3)];
+        assert_eq!(buf.len(), pack_sizes.checked_par_buf_len(3).unwrap());
+        let (bpack, apacks) = pack_sizes.split_par_buf(3, &mut buf);
+        assert_eq!([bpack.len(), apacks.len()], [6, 36]);
+    }
 }
@@ -0,0 +1,93 @@
+use crate::gemm::check_gemm_args;
+use crate::par_loops::par_gemm_loops;
+use crate::{Kernel, MatMut, MatRef, PackSizes};
+use num_traits::{One, Zero};
+use std::vec;
+
+// Packs each panel of `b` once, shared by the threads of the current pool, which compute
+// the ic loop in parallel with an A buffer each, see `par_gemm_loops`.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn par_gemm_with_kernel<T, K>(
+    kernel: &K,
+    alpha: T,
+    a: MatRef<T>,
+    b: MatRef<T>,
+    beta: T,
+    c: &mut MatMut<T>,
+    pack_sizes: PackSizes,
+) where
+    T: Copy + Zero + One + Send + Sync,
+    K: Kernel<Scalar = T> + Sync + ?Sized,
+{
+    let [mr, nr] = [K::MR, K::NR];
+    let buf_len = pack_sizes.checked_buf_len().unwrap_or_default();
+    let pack_sizes = match check_gemm_args(mr, nr, a, b, c.to_ref(), pack_sizes, buf_len) {
+        Ok(pack_sizes) => pack_sizes,
+        Err(err) => panic!("{err}"),
+    };
+
+    let threads = rayon::current_num_threads().max(1);
+    let PackSizes { mc, kc, nc } = pack_sizes;
+    let mut bpack = vec![T::zero(); kc * nc];
+    let mut apacks = vec![T::zero(); threads * mc * kc];
+    let run = |task: &(dyn Fn() + Sync)| {
+        rayon::broadcast(|_| task());
+    };
+    par_gemm_loops(
+        kernel,
+        alpha,
+        a,
+        b,
+        beta,
+        c,
+        pack_sizes,
+        &mut bpack,
+        &mut apacks,
+        &run,
+    );
+}
+
+#[cfg(not(miri))]
+#[cfg(test)]
+mod proptests {
+    use crate::{
+        as_mut,
+        kernels::{GenericKernel4x4, GenericKernel8x8},
+        utils::{arb_matrix_triple_with, naive_gemm},
+        Kernel, PackSizes,
+    };
+    use proptest::prelude::*;
+    use rayon::ThreadPoolBuilder;
+
+    proptest! {
+        #[test]
+        fn proptest_par_gemm(
+            [a, b, c] in arb_matrix_triple_with(0..50, 0..50, 0..50, -10i32..10),
+            alpha in -10i32..10,
+            beta in -10i32..10,
+            [mc, kc, nc] in [1..4usize, 1..20, 1..4],
+            threads in 1..=8usize,
+        ) {
+            let pool = ThreadPoolBuilder::new().num_threads(threads).build().unwrap();
+
+            let mut expect = c.clone();
+            naive_gemm(alpha, a.to_ref(), b.to_ref(), beta, as_mut!(expect));
+
+            let kernel = GenericKernel4x4::new();
+            let pack_sizes = PackSizes { mc: mc * kernel.mr(), kc, nc: nc * kernel.nr() };
+            let mut actual = c.clone();
+            pool.install(|| {
+                kernel.par_gemm(alpha, a.to_ref(), b.to_ref(), beta, as_mut!(actual), pack_sizes)
+            });
+            prop_assert_eq!(expect.as_slice(), actual.as_slice());
+
+            let kernel = GenericKernel8x8::new();
+            let pack_sizes = PackSizes { mc: mc * kernel.mr(), kc, nc: nc * kernel.nr() };
+            let mut actual = c;
+            pool.install(|| {
+                kernel.par_gemm(alpha, a.to_ref(), b.to_ref(), beta, as_mut!(actual), pack_sizes)
+            });
+            prop_assert_eq!(expect.as_slice(), actual.as_slice());
+        }
+    }
+}
@@ -0,0 +1,188 @@
+use crate::gemm::gemm_loops;
+use crate::kernel::Multiply;
+use crate::packing::{block_len, Packer, Panel, PanelsB};
+use crate::{Epilogue, Kernel, MatMut, MatRef, PackSizes};
+use core::marker::PhantomData;
+use core::ops::Range;
+use core::sync::atomic::{AtomicUsize, Ordering};
+use generic_array::{sequence::GenericSequence, GenericArray};
+use num_traits::{One, Zero};
+
+type Product<L, R> = <L as Multiply<R>>::Output;
+
+// The jc, pc and ic loops of the parallel drivers for checked arguments and clamped `pack_sizes`.
+// For each (jc, pc), the kc x nc panel of `b` is packed once into `bpack`, of `kc * nc` values,
+// then the ic loop runs in parallel over groups of whole `mr x nr` tiles of rows of `c`,
+// one group per `mc * kc` buffer of `apacks`, in which each group packs its blocks of `a`.
+//
+// `run` calls the given task on each worker and returns when all of them are finished.
+// The task computes groups until there are none left, so the groups are computed once
+// however many times it is called. It is called once more on the current thread
+// to compute the groups left, if any.
+//
+// If the rows of `c` interleave in memory, the problem is transposed to c^T = b^T a^T
+// to split the columns of `c`, or computed as a single group if they interleave as well.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn par_gemm_loops<T, K, R>(
+    kernel: &K,
+    alpha: T,
+    a: MatRef<T>,
+    b: MatRef<T>,
+    beta: T,
+    c: &mut MatMut<T>,
+    pack_sizes: PackSizes,
+    bpack: &mut [T],
+    apacks: &mut [T],
+    run: &R,
+) where
+    T: Copy + Zero + One + Send + Sync,
+    K: Kernel<Scalar = T> + Sync + ?Sized,
+    R: Fn(&(dyn Fn() + Sync)) + ?Sized,
+{
+    let [mr, nr] = [K::MR, K::NR];
+    let [m, n, k] = [c.nrows(), c.ncols(), a.ncols()];
+    let PackSizes { mc, kc, nc } = pack_sizes;
+    let apack_len = mc * kc;
+    assert_eq!(bpack.len(), kc * nc);
+    assert!(apack_len <= apacks.len());
+
+    if m == 0 || n == 0 || k == 0 {
+        // no panels to pack, see `gemm_loops`
+        let zero = T::zero();
+        let mut dst_buf = GenericArray::<T, Product<K::Mr, K::Nr>>::generate(|_| zero);
+        gemm_loops(
+            kernel,
+            alpha,
+            &mut Packer::new(a, apacks),
+            &mut Packer::new(b, bpack),
+            beta,
+            c,
+            k,
+            pack_sizes,
+            dst_buf.as_mut_slice(),
+            None::<&mut dyn Epilogue<T>>,
+        );
+        return;
+    }
+    if !c.rows_are_separable() && c.cols_are_separable() {
+        let mut c = c.reborrow().t();
+        let [a, b] = [b.t(), a.t()];
+        par_gemm_loops(
+            kernel, alpha, a, b, beta, &mut c, pack_sizes, bpack, apacks, run,
+        );
+        return;
+    }
+
+    let workers = if c.rows_are_separable() {
+        apacks.len() / apack_len
+    } else {
+        1
+    };
+    let row_tiles = (m + mr - 1) / mr;
+    let group_rows = (row_tiles + workers - 1) / workers * mr;
+
+    for jc in (0..n).step_by(nc) {
+        let nc = block_len(nc, nr, n - jc);
+        let cols = jc..n.min(jc + nc);
+        for (l4, pc) in (0..k).step_by(kc).enumerate() {
+            let beta = if l4 == 0 { beta } else { One::one() };
+            let kc = (pc + kc).min(k) - pc;
+
+            let mut b_panels = Packer::new(b, &mut *bpack);
+            let bpack = b_panels.panel_b(nr, pc..pc + kc, jc..jc + nc);
+            let pack_sizes = PackSizes { mc, kc, nc };
+
+            let groups = RowGroups::new(c.reborrow(), group_rows, apacks, apack_len);
+            let task = || {
+                while let Some((rows, mut c, apack)) = groups.claim() {
+                    let a = a.submatrix(rows, pc..pc + kc);
+                    let mut c = c.submatrix_mut(0..c.nrows(), cols.clone());
+                    let zero = T::zero();
+                    let mut dst_buf = GenericArray::<T, Product<K::Mr, K::Nr>>::generate(|_| zero);
+                    gemm_loops(
+                        kernel,
+                        alpha,
+                        &mut Packer::new(a, apack),
+                        &mut Panel(bpack),
+                        beta,
+                        &mut c,
+                        kc,
+                        pack_sizes,
+                        dst_buf.as_mut_slice(),
+                        None::<&mut dyn Epilogue<T>>,
+                    );
+                }
+            };
+            run(&task);
+            task();
+        }
+    }
+}
+
+// Disjoint groups of `group_rows` rows of `c`, each with its own buffer of `apacks`,
+// handed out to the workers through a shared reference. Every group is claimed at most once,
+// so the views of the workers never alias.
+struct RowGroups<'a, T> {
+    c: *mut T,
+    nrows: usize,
+    ncols: usize,
+    row_stride: usize,
+    col_stride: usize,
+    group_rows: usize,
+    apacks: *mut T,
+    apack_len: usize,
+    len: usize,
+    next: AtomicUsize,
+    marker: PhantomData<&'a mut [T]>,
+}
+
+// SAFETY: the groups are disjoint and each is claimed by a single worker, see `claim`.
+unsafe impl<T: Send> Sync for RowGroups<'_, T> {}
+
+impl<'a, T> RowGroups<'a, T> {
+    // Splits `c` into `apacks.len() / apack_len` groups at most,
+    // the rows of `c` must not interleave in memory if there are several groups.
+    fn new(mut c: MatMut<'a, T>, group_rows: usize, apacks: &'a mut [T], apack_len: usize) -> Self {
+        let len = (c.nrows() + group_rows - 1) / group_rows;
+        assert!(len * apack_len <= apacks.len());
+        assert!(len <= 1 || c.rows_are_separable());
+        Self {
+            c: c.as_mut_ptr(),
+            nrows: c.nrows(),
+            ncols: c.ncols(),
+            row_stride: c.row_stride(),
+            col_stride: c.col_stride(),
+            group_rows,
+            apacks: apacks.as_mut_ptr(),
+            apack_len,
+            len,
+            next: AtomicUsize::new(0),
+            marker: PhantomData,
+        }
+    }
+
+    // Returns the rows of the next group, its view of `c` and its buffer, or `None` if all
+    // the groups have been claimed.
+    fn claim(&self) -> Option<(Range<usize>, MatMut<'a, T>, &'a mut [T])> {
+        let i = self.next.fetch_add(1, Ordering::Relaxed);
+        if i >= self.len {
+            return None;
+        }
+        let start = i * self.group_rows;
+        let rows = start..self.nrows.min(start + self.group_rows);
+        let [rs, cs] = [self.row_stride, self.col_stride];
+        let offset = start * rs;
+        let span = (rows.len() - 1) * rs + (self.ncols - 1) * cs + 1;
+        // SAFETY: `i` is claimed once. The values of the rows of the group lie in
+        // offset..offset + span of the values of `c`, which do not overlap those of the other
+        // groups since the rows of `c` do not interleave, or there is a single group.
+        let values = unsafe { core::slice::from_raw_parts_mut(self.c.add(offset), span) };
+        let c = MatMut::from_parts(rows.len(), self.ncols, values, rs, cs).unwrap();
+        // SAFETY: `i < self.len`, so the buffer lies in `apacks`, and `i` is claimed once.
+        let apack = unsafe {
+            let apack = self.apacks.add(i * self.apack_len);
+            core::slice::from_raw_parts_mut(apack, self.apack_len)
+        };
+        Some((rows, c, apack))
+    }
+}
@@ -0,0 +1,686 @@
+use crate::gemm::gemm_loops;
+use crate::kernel::Multiply;
+use crate::packing::{block_len, BlocksA, Packer, PanelsB};
+use crate::{Epilogue, GemmError, Kernel, MatMut, MatRef, PackSizes};
+use core::{marker::PhantomData, ops::Range};
+use generic_array::{sequence::GenericSequence, GenericArray};
+use num_traits::{One, Zero};
+
+type Product<L, R> = <L as Multiply<R>>::Output;
+
+/// Matrix `b` packed once into the `kc x nc` panels read by the microkernel,
+/// so that it can be reused by many calls of [`Kernel::gemm_prepacked_b`].
+///
+/// # Layout
+///
+/// The layout is stable and can be stored and restored with [`as_slice`](Self::as_slice)
+/// and [`from_parts`](Self::from_parts). For a `k x n` matrix `b`:
+///
+/// 1. The columns are split into blocks of `nc` columns, where `nc` is `pack_sizes.nc`
+///    rounded down to a multiple of `nr`. The last block is padded with zeros
+///    up to a multiple of `nr` columns, so the block starting at column `jc` has
+///    `block_nc = min(nc, round_up(n - jc, nr))` columns and begins at offset `jc * k`.
+/// 2. Within a block, the rows are split into blocks of `kc` rows.
+///    The block of rows starting at `pc` has `kc' = min(kc, k - pc)` rows
+///    and begins at offset `pc * block_nc` of the column block.
+/// 3. Each `kc' x block_nc` panel is stored as `block_nc / nr` consecutive panels of `nr` columns.
+///    A panel is row-major with `nr` contiguous values per row,
+///    so `b[pc + p, jc + jr + j]` is at `jr * kc' + p * nr + j` of the panel.
+///
+/// The required length of the buffer is given by [`PackSizes::packed_b_len`].
+///
+/// # Examples
+///
+/// ```
+/// use microgemm::{kernels::GenericKernel2x2, Kernel as _, MatMut, MatRef, PackSizes, PackedB};
+///
+/// let kernel = GenericKernel2x2::<i32>::new();
+/// let pack_sizes = PackSizes { mc: 2, kc: 2, nc: 2 };
+///
+/// let b = MatRef::row_major(3, 2, &[10, 11, 20, 21, 30, 31]);
+/// let mut buf = [0; 3 * 2];
+/// assert_eq!(buf.len(), pack_sizes.packed_b_len(&kernel, 3, 2));
+/// let b = PackedB::pack_into(&kernel, b, pack_sizes, &mut buf[..]);
+///
+/// let mut packing_buf = [0; 2 * 2];
+/// for scale in 1..3 {
+///     let a = [1, 2, 3, 4, 5, 6].map(|x| scale * x);
+///     let a = MatRef::row_major(2, 3, &a);
+///     let mut c = [0; 2 * 2];
+///     let mut c = MatMut::row_major(2, 2, &mut c);
+///     kernel.gemm_prepacked_b(1, a, &b, 0, &mut c, &mut packing_buf);
+///     assert_eq!(c.as_slice(), [140, 146, 320, 335].map(|x| scale * x));
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct PackedB<V, T> {
+    values: V,
+    k: usize,
+    n: usize,
+    nr: usize,
+    pack_sizes: PackSizes,
+    marker: PhantomData<T>,
+}
+
+impl<V, T> PackedB<V, T> {
+    /// Number of rows of the packed matrix.
+    pub fn k(&self) -> usize {
+        self.k
+    }
+    /// Number of columns of the packed matrix.
+    pub fn n(&self) -> usize {
+        self.n
+    }
+    /// `nr` of the kernel the matrix was packed for.
+    pub fn nr(&self) -> usize {
+        self.nr
+    }
+    /// `PackSizes` the matrix was packed with.
+    pub fn pack_sizes(&self) -> PackSizes {
+        self.pack_sizes
+    }
+    fn nc(&self) -> usize {
+        self.pack_sizes.nc - self.pack_sizes.nc % self.nr
+    }
+}
+
+impl<V, T> PackedB<V, T>
+where
+    V: AsRef<[T]>,
+{
+    /// Restores a matrix packed for a kernel with the given `nr` from its values,
+    /// e.g. ones previously obtained by [`as_slice`](Self::as_slice).
+    /// Returns `None` if `pack_sizes` is too small for `nr`
+    /// or the length of `values` does not match the layout.
+    pub fn from_parts(
+        values: V,
+        k: usize,
+        n: usize,
+        nr: usize,
+        pack_sizes: PackSizes,
+    ) -> Option<Self> {
+        if nr == 0 || pack_sizes.nc < nr || pack_sizes.kc == 0 {
+            return None;
+        }
+        let mat = Self {
+            values,
+            k,
+            n,
+            nr,
+            pack_sizes,
+            marker: PhantomData,
+        };
+        let len = pack_sizes.checked_packed_b_len(nr, k, n)?;
+        if mat.as_slice().len() == len {
+            Some(mat)
+        } else {
+            None
+        }
+    }
+    /// Extracts a slice containing the packed values.
+    pub fn as_slice(&self) -> &[T] {
+        self.values.as_ref()
+    }
+    // The packed panel b[pc..pc + kc, jc..jc + nc] and the padded number of its columns.
+    fn panel(&self, jc: usize, pc: usize) -> (&[T], usize) {
+        let [k, nr, nc] = [self.k, self.nr, self.nc()];
+        let kc = (pc + self.pack_sizes.kc).min(k) - pc;
+        let block_nc = block_len(nc, nr, self.n - jc);
+        let offset = jc * k + pc * block_nc;
+        (&self.as_slice()[offset..offset + kc * block_nc], block_nc)
+    }
+}
+
+impl<V, T> PackedB<V, T>
+where
+    V: AsMut<[T]>,
+    T: Copy + Zero,
+{
+    /// Packs `b` into `buf` for `kernel`, without allocations.
+    ///
+    /// # Panics
+    /// 1. If `pack_sizes.nc < kernel.nr()` or `pack_sizes.kc` is zero.
+    /// 2. If `buf.as_mut().len() != pack_sizes.packed_b_len(kernel, b.nrows(), b.ncols())`.
+    pub fn pack_into<K>(kernel: &K, b: MatRef<T>, pack_sizes: PackSizes, mut buf: V) -> Self
+    where
+        K: Kernel<Scalar = T> + ?Sized,
+    {
+        let expected = pack_sizes.packed_b_len(kernel, b.nrows(), b.ncols());
+        let values = buf.as_mut();
+        assert_eq!(values.len(), expected);
+
+        let [k, n, nr] = [b.nrows(), b.ncols(), K::NR];
+        let nc = pack_sizes.nc - pack_sizes.nc % nr;
+        for jc in (0..n).step_by(nc) {
+            let block_nc = block_len(nc, nr, n - jc);
+            let block = &mut values[jc * k..jc * k + k * block_nc];
+            for pc in (0..k).step_by(pack_sizes.kc) {
+                let kc = (pc + pack_sizes.kc).min(k) - pc;
+                let bpack = &mut block[pc * block_nc..(pc + kc) * block_nc];
+                crate::packing::pack_b(nr, bpack, b, pc..pc + kc, jc..jc + block_nc);
+            }
+        }
+        Self {
+            values: buf,
+            k,
+            n,
+            nr,
+            pack_sizes,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<V, T> PanelsB<T> for &PackedB<V, T>
+where
+    V: AsRef<[T]>,
+{
+    fn panel_b(&mut self, nr: usize, rows: Range<usize>, cols: Range<usize>) -> &[T] {
+        let (panel, nc) = self.panel(cols.start, rows.start);
+        debug_assert_eq!([nr, nc], [self.nr, cols.len()]);
+        debug_assert_eq!(panel.len(), rows.len() * nc);
+        panel
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> PackedB<std::vec::Vec<T>, T>
+where
+    T: Copy + Zero,
+{
+    /// Packs `b` for `kernel` into a newly allocated buffer.
+    ///
+    /// # Panics
+    /// If `pack_sizes.nc < kernel.nr()` or `pack_sizes.kc` is zero.
+    pub fn pack<K>(kernel: &K, b: MatRef<T>, pack_sizes: PackSizes) -> Self
+    where
+        K: Kernel<Scalar = T> + ?Sized,
+    {
+        let len = pack_sizes.packed_b_len(kernel, b.nrows(), b.ncols());
+        Self::pack_into(kernel, b, pack_sizes, vec![T::zero(); len])
+    }
+}
+
+/// Matrix `a` packed once into the `mc x kc` blocks read by the microkernel,
+/// so that it can be reused by many calls of [`Kernel::gemm_prepacked_a`].
+///
+/// # Layout
+///
+/// The layout is stable and can be stored and restored with [`as_slice`](Self::as_slice)
+/// and [`from_parts`](Self::from_parts). For an `m x k` matrix `a`:
+///
+/// 1. The rows are split into blocks of `mc` rows, where `mc` is `pack_sizes.mc`
+///    rounded down to a multiple of `mr`. The last block is padded with zeros
+///    up to a multiple of `mr` rows, so the block starting at row `ic` has
+///    `block_mc = min(mc, round_up(m - ic, mr))` rows and begins at offset `ic * k`.
+/// 2. Within a block, the columns are split into blocks of `kc` columns.
+///    The block of columns starting at `pc` has `kc' = min(kc, k - pc)` columns
+///    and begins at offset `pc * block_mc` of the row block.
+/// 3. Each `block_mc x kc'` block is stored as `block_mc / mr` consecutive panels of `mr` rows.
+///    A panel is col-major with `mr` contiguous values per column,
+///    so `a[ic + ir + i, pc + p]` is at `ir * kc' + p * mr + i` of the block.
+///
+/// The required length of the buffer is given by [`PackSizes::packed_a_len`].
+///
+/// # Examples
+///
+/// ```
+/// use microgemm::{kernels::GenericKernel2x2, Kernel as _, MatMut, MatRef, PackSizes, PackedA};
+///
+/// let kernel = GenericKernel2x2::<i32>::new();
+/// let pack_sizes = PackSizes { mc: 2, kc: 2, nc: 2 };
+///
+/// let a = MatRef::row_major(2, 3, &[1, 2, 3, 4, 5, 6]);
+/// let mut buf = [0; 2 * 3];
+/// assert_eq!(buf.len(), pack_sizes.packed_a_len(&kernel, 2, 3));
+/// let a = PackedA::pack_into(&kernel, a, pack_sizes, &mut buf[..]);
+/// assert_eq!(a.as_slice(), [1, 4, 2, 5, 3, 6]);
+///
+/// let mut packing_buf = [0; 2 * 2];
+/// for scale in 1..3 {
+///     let b = [10, 11, 20, 21, 30, 31].map(|x| scale * x);
+///     let b = MatRef::row_major(3, 2, &b);
+///     let mut c = [0; 2 * 2];
+///     let mut c = MatMut::row_major(2, 2, &mut c);
+///     kernel.gemm_prepacked_a(1, &a, b, 0, &mut c, &mut packing_buf);
+///     assert_eq!(c.as_slice(), [140, 146, 320, 335].map(|x| scale * x));
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct PackedA<V, T> {
+    values: V,
+    m: usize,
+    k: usize,
+    mr: usize,
+    pack_sizes: PackSizes,
+    marker: PhantomData<T>,
+}
+
+impl<V, T> PackedA<V, T> {
+    /// Number of rows of the packed matrix.
+    pub fn m(&self) -> usize {
+        self.m
+    }
+    /// Number of columns of the packed matrix.
+    pub fn k(&self) -> usize {
+        self.k
+    }
+    /// `mr` of the kernel the matrix was packed for.
+    pub fn mr(&self) -> usize {
+        self.mr
+    }
+    /// `PackSizes` the matrix was packed with.
+    pub fn pack_sizes(&self) -> PackSizes {
+        self.pack_sizes
+    }
+    fn mc(&self) -> usize {
+        self.pack_sizes.mc - self.pack_sizes.mc % self.mr
+    }
+}
+
+impl<V, T> PackedA<V, T>
+where
+    V: AsRef<[T]>,
+{
+    /// Restores a matrix packed for a kernel with the given `mr` from its values,
+    /// e.g. ones previously obtained by [`as_slice`](Self::as_slice).
+    /// Returns `None` if `pack_sizes` is too small for `mr`
+    /// or the length of `values` does not match the layout.
+    pub fn from_parts(
+        values: V,
+        m: usize,
+        k: usize,
+        mr: usize,
+        pack_sizes: PackSizes,
+    ) -> Option<Self> {
+        if mr == 0 || pack_sizes.mc < mr || pack_sizes.kc == 0 {
+            return None;
+        }
+        let mat = Self {
+            values,
+            m,
+            k,
+            mr,
+            pack_sizes,
+            marker: PhantomData,
+        };
+        let len = pack_sizes.checked_packed_a_len(mr, m, k)?;
+        if mat.as_slice().len() == len {
+            Some(mat)
+        } else {
+            None
+        }
+    }
+    /// Extracts a slice containing the packed values.
+    pub fn as_slice(&self) -> &[T] {
+        self.values.as_ref()
+    }
+    // The packed block a[ic..ic + mc, pc..pc + kc] and the padded number of its rows.
+    fn block(&self, ic: usize, pc: usize) -> (&[T], usize) {
+        let [k, mr, mc] = [self.k, self.mr, self.mc()];
+        let kc = (pc + self.pack_sizes.kc).min(k) - pc;
+        let block_mc = block_len(mc, mr, self.m - ic);
+        let offset = ic * k + pc * block_mc;
+        (&self.as_slice()[offset..offset + block_mc * kc], block_mc)
+    }
+}
+
+impl<V, T> PackedA<V, T>
+where
+    V: AsMut<[T]>,
+    T: Copy + Zero,
+{
+    /// Packs `a` into `buf` for `kernel`, without allocations.
+    ///
+    /// # Panics
+    /// 1. If `pack_sizes.mc < kernel.mr()` or `pack_sizes.kc` is zero.
+    /// 2. If `buf.as_mut().len() != pack_sizes.packed_a_len(kernel, a.nrows(), a.ncols())`.
+    pub fn pack_into<K>(kernel: &K, a: MatRef<T>, pack_sizes: PackSizes, mut buf: V) -> Self
+    where
+        K: Kernel<Scalar = T> + ?Sized,
+    {
+        let expected = pack_sizes.packed_a_len(kernel, a.nrows(), a.ncols());
+        let values = buf.as_mut();
+        assert_eq!(values.len(), expected);
+
+        let [m, k, mr] = [a.nrows(), a.ncols(), K::MR];
+        let mc = pack_sizes.mc - pack_sizes.mc % mr;
+        for ic in (0..m).step_by(mc) {
+            let block_mc = block_len(mc, mr, m - ic);
+            let block = &mut values[ic * k..ic * k + block_mc * k];
+            for pc in (0..k).step_by(pack_sizes.kc) {
+                let kc = (pc + pack_sizes.kc).min(k) - pc;
+                let apack = &mut block[pc * block_mc..(pc + kc) * block_mc];
+                crate::packing::pack_a(mr, apack, a, ic..ic + block_mc, pc..pc + kc);
+            }
+        }
+        Self {
+            values: buf,
+            m,
+            k,
+            mr,
+            pack_sizes,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<V, T> BlocksA<T> for &PackedA<V, T>
+where
+    V: AsRef<[T]>,
+{
+    fn block_a(&mut self, mr: usize, rows: Range<usize>, cols: Range<usize>) -> &[T] {
+        let (block, mc) = self.block(rows.start, cols.start);
+        debug_assert_eq!([mr, mc], [self.mr, rows.len()]);
+        debug_assert_eq!(block.len(), mc * cols.len());
+        block
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> PackedA<std::vec::Vec<T>, T>
+where
+    T: Copy + Zero,
+{
+    /// Packs `a` for `kernel` into a newly allocated buffer.
+    ///
+    /// # Panics
+    /// If `pack_sizes.mc < kernel.mr()` or `pack_sizes.kc` is zero.
+    pub fn pack<K>(kernel: &K, a: MatRef<T>, pack_sizes: PackSizes) -> Self
+    where
+        K: Kernel<Scalar = T> + ?Sized,
+    {
+        let len = pack_sizes.packed_a_len(kernel, a.nrows(), a.ncols());
+        Self::pack_into(kernel, a, pack_sizes, vec![T::zero(); len])
+    }
+}
+
+pub(crate) fn try_gemm_prepacked_b<T, K, V>(
+    kernel: &K,
+    alpha: T,
+    a: MatRef<T>,
+    b: &PackedB<V, T>,
+    beta: T,
+    c: &mut MatMut<T>,
+    packing_buf: &mut [T],
+) -> Result<(), GemmError>
+where
+    T: Copy + Zero + One,
+    K: Kernel<Scalar = T> + ?Sized,
+    V: AsRef<[T]>,
+{
+    let [mr, nr] = [K::MR, K::NR];
+    if b.nr != nr {
+        return Err(GemmError::PackedTileMismatch {
+            packed: b.nr,
+            kernel: nr,
+        });
+    }
+    if a.nrows() != c.nrows() || a.ncols() != b.k || b.n != c.ncols() {
+        return Err(GemmError::DimMismatch {
+            a: [a.nrows(), a.ncols()],
+            b: [b.k, b.n],
+            c: [c.nrows(), c.ncols()],
+        });
+    }
+    let pack_sizes = b.pack_sizes;
+    if pack_sizes.mc < mr {
+        return Err(GemmError::PackSizesTooSmall { pack_sizes, mr, nr });
+    }
+    let packed_len = pack_sizes
+        .checked_packed_b_len(nr, b.k, b.n)
+        .ok_or(GemmError::Overflow)?;
+    if b.as_slice().len() != packed_len {
+        return Err(GemmError::BufferLen {
+            expected: packed_len,
+            got: b.as_slice().len(),
+        });
+    }
+    let expected = pack_sizes
+        .mc
+        .checked_mul(pack_sizes.kc)
+        .ok_or(GemmError::Overflow)?;
+    if packing_buf.len() != expected {
+        return Err(GemmError::BufferLen {
+            expected,
+            got: packing_buf.len(),
+        });
+    }
+
+    let zero = T::zero();
+    let mut dst_buf = GenericArray::<T, Product<K::Mr, K::Nr>>::generate(|_| zero);
+    let k = a.ncols();
+    let pack_sizes = pack_sizes.clamped(mr, nr);
+    let epilogue = None::<&mut dyn Epilogue<T>>;
+    gemm_loops(
+        kernel,
+        alpha,
+        &mut Packer::new(a, packing_buf),
+        &mut &*b,
+        beta,
+        c,
+        k,
+        pack_sizes,
+        dst_buf.as_mut_slice(),
+        epilogue,
+    );
+    Ok(())
+}
+
+pub(crate) fn try_gemm_prepacked_a<T, K, V>(
+    kernel: &K,
+    alpha: T,
+    a: &PackedA<V, T>,
+    b: MatRef<T>,
+    beta: T,
+    c: &mut MatMut<T>,
+    packing_buf: &mut [T],
+) -> Result<(), GemmError>
+where
+    T: Copy + Zero + One,
+    K: Kernel<Scalar = T> + ?Sized,
+    V: AsRef<[T]>,
+{
+    let [mr, nr] = [K::MR, K::NR];
+    if a.mr != mr {
+        return Err(GemmError::PackedTileMismatch {
+            packed: a.mr,
+            kernel: mr,
+        });
+    }
+    if a.m != c.nrows() || a.k != b.nrows() || b.ncols() != c.ncols() {
+        return Err(GemmError::DimMismatch {
+            a: [a.m, a.k],
+            b: [b.nrows(), b.ncols()],
+            c: [c.nrows(), c.ncols()],
+        });
+    }
+    let pack_sizes = a.pack_sizes;
+    if pack_sizes.nc < nr {
+        return Err(GemmError::PackSizesTooSmall { pack_sizes, mr, nr });
+    }
+    let expected = pack_sizes
+        .kc
+        .checked_mul(pack_sizes.nc)
+        .ok_or(GemmError::Overflow)?;
+    if packing_buf.len() != expected {
+        return Err(GemmError::BufferLen {
+            expected,
+            got: packing_buf.len(),
+        });
+    }
+
+    let zero = T::zero();
+    let mut dst_buf = GenericArray::<T, Product<K::Mr, K::Nr>>::generate(|_| zero);
+    let k = b.nrows();
+    let pack_sizes = pack_sizes.clamped(mr, nr);
+    let epilogue = None::<&mut dyn Epilogue<T>>;
+    gemm_loops(
+        kernel,
+        alpha,
+        &mut &*a,
+        &mut Packer::new(b, packing_buf),
+        beta,
+        c,
+        k,
+        pack_sizes,
+        dst_buf.as_mut_slice(),
+        epilogue,
+    );
+    Ok(())
+}
+
+#[cfg(not(miri))]
+#[cfg(test)]
+mod proptests {
+    use super::*;
+    use crate::{
+        as_mut,
+        kernels::{GenericKernel4x4, GenericKernel8x8},
+        std_prelude::*,
+        utils::{arb_matrix_triple_with, naive_gemm},
+    };
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn proptest_gemm_prepacked_b(
+            [a, b, c] in arb_matrix_triple_with(0..40, 0..40, 0..40, -10i32..10),
+            alpha in -10i32..10,
+            beta in -10i32..10,
+            [mc, kc, nc] in [1..4usize, 1..20, 4..20],
+        ) {
+            let mut expect = c.clone();
+            naive_gemm(alpha, a.to_ref(), b.to_ref(), beta, as_mut!(expect));
+
+            let kernel = GenericKernel4x4::new();
+            let pack_sizes = PackSizes { mc: mc * kernel.mr(), kc, nc };
+            let mut buf = vec![-1; pack_sizes.packed_b_len(&kernel, b.nrows(), b.ncols())];
+            let packed = PackedB::pack_into(&kernel, b.to_ref(), pack_sizes, buf.as_mut_slice());
+            let mut packing_buf = vec![-1; pack_sizes.mc * pack_sizes.kc];
+            let mut actual = c.clone();
+            kernel.gemm_prepacked_b(alpha, a.to_ref(), &packed, beta, as_mut!(actual), &mut packing_buf);
+            prop_assert_eq!(expect.as_slice(), actual.as_slice());
+
+            let kernel = GenericKernel8x8::new();
+            let pack_sizes = PackSizes { mc: mc * kernel.mr(), kc, nc: nc.max(kernel.nr()) };
+            let mut buf = vec![-1; pack_sizes.packed_b_len(&kernel, b.nrows(), b.ncols())];
+            PackedB::pack_into(&kernel, b.to_ref(), pack_sizes, buf.as_mut_slice());
+            let packed = PackedB::from_parts(&buf[..], b.nrows(), b.ncols(), 8, pack_sizes).unwrap();
+            let mut packing_buf = vec![-1; pack_sizes.mc * pack_sizes.kc];
+            let mut actual = c;
+            kernel.gemm_prepacked_b(alpha, a.to_ref(), &packed, beta, as_mut!(actual), &mut packing_buf);
+            prop_assert_eq!(expect.as_slice(), actual.as_slice());
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn proptest_gemm_prepacked_a(
+            [a, b, c] in arb_matrix_triple_with(0..40, 0..40, 0..40, -10i32..10),
+            alpha in -10i32..10,
+            beta in -10i32..10,
+            [mc, kc, nc] in [4..20usize, 1..20, 1..4],
+        ) {
+            let mut expect = c.clone();
+            naive_gemm(alpha, a.to_ref(), b.to_ref(), beta, as_mut!(expect));
+
+            let kernel = GenericKernel4x4::new();
+            let pack_sizes = PackSizes { mc, kc, nc: nc * kernel.nr() };
+            let mut buf = vec![-1; pack_sizes.packed_a_len(&kernel, a.nrows(), a.ncols())];
+            let packed = PackedA::pack_into(&kernel, a.to_ref(), pack_sizes, buf.as_mut_slice());
+            let mut packing_buf = vec![-1; pack_sizes.kc * pack_sizes.nc];
+            let mut actual = c.clone();
+            kernel.gemm_prepacked_a(alpha, &packed, b.to_ref(), beta, as_mut!(actual), &mut packing_buf);
+            prop_assert_eq!(expect.as_slice(), actual.as_slice());
+
+            let kernel = GenericKernel8x8::new();
+            let pack_sizes = PackSizes { mc: mc.max(kernel.mr()), kc, nc: nc * kernel.nr() };
+            let mut buf = vec![-1; pack_sizes.packed_a_len(&kernel, a.nrows(), a.ncols())];
+            PackedA::pack_into(&kernel, a.to_ref(), pack_sizes, buf.as_mut_slice());
+            let packed = PackedA::from_parts(&buf[..], a.nrows(), a.ncols(), 8, pack_sizes).unwrap();
+            let mut packing_buf = vec![-1; pack_sizes.kc * pack_sizes.nc];
+            let mut actual = c;
+            kernel.gemm_prepacked_a(alpha, &packed, b.to_ref(), beta, as_mut!(actual), &mut packing_buf);
+            prop_assert_eq!(expect.as_slice(), actual.as_slice());
+        }
+    }
+
+    #[rustfmt::skip]
+    #[test]
+    fn test_packed_a_layout() {
+        let a = [
+            1, 2, 3,
+            4, 5, 6,
+            7, 8, 9,
+        ];
+        let a = MatRef::row_major(3, 3, &a);
+        let kernel = GenericKernel4x4::new();
+        let pack_sizes = PackSizes { mc: 4, kc: 2, nc: 4 };
+        let mut buf = [-1; 4 * 3];
+        let packed = PackedA::pack_into(&kernel, a, pack_sizes, &mut buf[..]);
+        let expect = [
+            1, 4, 7, 0, 2, 5, 8, 0,
+            3, 6, 9, 0,
+        ];
+        assert_eq!(packed.as_slice(), expect);
+
+        assert!(PackedA::from_parts(&expect[..], 3, 3, 4, pack_sizes).is_some());
+        assert!(PackedA::from_parts(&expect[..], 3, 4, 4, pack_sizes).is_none());
+        assert!(PackedA::from_parts(&expect[..], 3, 3, 8, pack_sizes).is_none());
+    }
+
+    #[rustfmt::skip]
+    #[test]
+    fn test_packed_b_layout() {
+        let b = [
+            1, 2, 3,
+            4, 5, 6,
+            7, 8, 9,
+        ];
+        let b = MatRef::row_major(3, 3, &b);
+        let kernel = GenericKernel4x4::new();
+        let pack_sizes = PackSizes { mc: 4, kc: 2, nc: 4 };
+        let mut buf = [-1; 3 * 4];
+        let packed = PackedB::pack_into(&kernel, b, pack_sizes, &mut buf[..]);
+        let expect = [
+            1, 2, 3, 0, 4, 5, 6, 0,
+            7, 8, 9, 0,
+        ];
+        assert_eq!(packed.as_slice(), expect);
+
+        assert!(PackedB::from_parts(&expect[..], 3, 3, 4, pack_sizes).is_some());
+        assert!(PackedB::from_parts(&expect[..], 4, 3, 4, pack_sizes).is_none());
+        assert!(PackedB::from_parts(&expect[..], 3, 3, 8, pack_sizes).is_none());
+    }
+
+    #[test]
+    fn test_gemm_prepacked_b_kernel_mismatch() {
+        let pack_sizes = PackSizes {
+            mc: 8,
+            kc: 2,
+            nc: 8,
+        };
+        let b = MatRef::row_major(1, 1, &[1]);
+        let mut buf = [0; 4];
+        let packed = PackedB::pack_into(&GenericKernel4x4::new(), b, pack_sizes, &mut buf[..]);
+
+        let mut c = [0];
+        let mut c = MatMut::row_major(1, 1, &mut c);
+        let mut packing_buf = vec![0; 8 * 2];
+        let result = try_gemm_prepacked_b(
+            &GenericKernel8x8::new(),
+            1,
+            b,
+            &packed,
+            0,
+            &mut c,
+            &mut packing_buf,
+        );
+        let expect = GemmError::PackedTileMismatch {
+            packed: 4,
+            kernel: 8,
+        };
+        assert_eq!(result, Err(expect));
+    }
+}
@@ -0,0 +1,304 @@
+//! Naive gemm for cross-checking the results of kernels, available in `no_std` and `no_alloc` environments.
+
+use crate::{GemmError, Kernel, MatMut, MatRef, PackSizes, Zero};
+use core::ops::{Add, Mul};
+use num_traits::{float::FloatCore, ToPrimitive};
+
+/// Reference `c <- alpha a b + beta c` computed with one dot product per element of `c`.
+/// `c` is not read if `beta` is zero.
+pub fn naive_gemm<T>(alpha: T, a: MatRef<T>, b: MatRef<T>, beta: T, c: &mut MatMut<T>)
+where
+    T: Copy + Add<Output = T> + Mul<Output = T> + Zero,
+{
+    assert_eq!(a.nrows(), c.nrows());
+    assert_eq!(b.ncols(), c.ncols());
+    assert_eq!(a.ncols(), b.nrows());
+
+    let k = a.ncols();
+
+    for i in 0..a.nrows() {
+        for j in 0..b.ncols() {
+            let dot = (0..k)
+                .map(|h| a.get(i, h) * b.get(h, j))
+                .reduce(|accum, x| accum + x)
+                .unwrap_or(T::zero());
+            let z = c.get_mut(i, j);
+            *z = if beta.is_zero() {
+                alpha * dot
+            } else {
+                alpha * dot + beta * *z
+            };
+        }
+    }
+}
+
+/// Largest deviations of the values computed by a kernel from the reference ones,
+/// returned by [`checked_gemm`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Deviation {
+    /// `max |actual - expect|`.
+    pub max_abs: f64,
+    /// `max |actual - expect| / |expect|`, where an exact match counts as zero
+    /// and any difference from a zero `expect` as infinity.
+    pub max_rel: f64,
+}
+
+/// Computes `c <- alpha a b + beta c` with `kernel.try_gemm` and also with [`naive_gemm`],
+/// and returns how far the result of the kernel deviates from the reference.
+///
+/// `expect_buf` must have `m * n` elements, it receives the reference `c` in row-major order.
+/// Values that cannot be converted to `f64` are treated as `NaN`.
+///
+/// # Examples
+///
+/// ```
+/// use microgemm::{kernels::GenericKernel4x4, reference::checked_gemm, MatMut, MatRef, PackSizes};
+///
+/// let kernel = GenericKernel4x4::<f32>::new();
+/// let pack_sizes = PackSizes { mc: 4, kc: 3, nc: 4 };
+/// let mut packing_buf = [0.0; 24];
+///
+/// let a = MatRef::row_major(2, 3, &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+/// let b = MatRef::row_major(3, 1, &[0.5, 0.25, 0.125]);
+/// let mut c = [1.0, 1.0];
+/// let mut c = MatMut::row_major(2, 1, &mut c);
+/// let mut expect = [0.0; 2];
+///
+/// let deviation =
+///     checked_gemm(&kernel, 1.0, a, b, 1.0, &mut c, pack_sizes, &mut packing_buf, &mut expect)
+///         .unwrap();
+/// assert_eq!(expect, [2.375, 5.0]);
+/// assert_eq!(deviation.max_abs, 0.0);
+/// ```
+#[allow(clippy::too_many_arguments)]
+pub fn checked_gemm<T, K>(
+    kernel: &K,
+    alpha: T,
+    a: MatRef<T>,
+    b: MatRef<T>,
+    beta: T,
+    c: &mut MatMut<T>,
+    pack_sizes: PackSizes,
+    packing_buf: &mut [T],
+    expect_buf: &mut [T],
+) -> Result<Deviation, GemmError>
+where
+    K: Kernel<Scalar = T> + ?Sized,
+    T: Copy + Add<Output = T> + Mul<Output = T> + Zero + ToPrimitive,
+{
+    let [m, n] = [c.nrows(), c.ncols()];
+    if expect_buf.len() != m * n {
+        return Err(GemmError::BufferLen {
+            expected: m * n,
+            got: expect_buf.len(),
+        });
+    }
+    let mut expect = MatMut::row_major(m, n, expect_buf);
+    for i in 0..m {
+        for j in 0..n {
+            *expect.get_mut(i, j) = if beta.is_zero() {
+                T::zero()
+            } else {
+                c.get(i, j)
+            };
+        }
+    }
+
+    kernel.try_gemm(alpha, a, b, beta, c, pack_sizes, packing_buf)?;
+    naive_gemm(alpha, a, b, beta, &mut expect);
+
+    let to_f64 = |x: T| x.to_f64().unwrap_or(f64::NAN);
+    let mut deviation = Deviation {
+        max_abs: 0.0,
+        max_rel: 0.0,
+    };
+    for i in 0..m {
+        for j in 0..n {
+            let [actual, expect] = [to_f64(c.get(i, j)), to_f64(expect.get(i, j))];
+            let abs = FloatCore::abs(actual - expect);
+            let rel = if abs == 0.0 {
+                0.0
+            } else {
+                abs / FloatCore::abs(expect)
+            };
+            deviation.max_abs = max_or_nan(deviation.max_abs, abs);
+            deviation.max_rel = max_or_nan(deviation.max_rel, rel);
+        }
+    }
+    Ok(deviation)
+}
+
+// Unlike `f64::max`, propagates `NaN`, so that a `NaN` result is never reported as a match.
+fn max_or_nan(x: f64, y: f64) -> f64 {
+    if x.is_nan() || y.is_nan() {
+        f64::NAN
+    } else if x < y {
+        y
+    } else {
+        x
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[rustfmt::skip]
+    #[test]
+    fn fixed_1() {
+        let a = [
+            1, 2, 3,
+            4, 5, 6,
+        ];
+        let b = [
+            10, 11,
+            20, 21,
+            30, 31,
+        ];
+        let a = MatRef::row_major(2, 3, &a);
+        let b = MatRef::row_major(3, 2, &b);
+
+        let mut c = [-1; 4];
+        let mut c = MatMut::row_major(2, 2, c.as_mut());
+        let expect = [
+            140, 146,
+            320, 335,
+        ];
+        naive_gemm(1, a, b, 0, c.as_mut());
+        assert_eq!(c.as_slice(), expect);
+
+        let mut c = [-1; 4];
+        let mut c = MatMut::col_major(2, 2, c.as_mut());
+        let expect = [
+            140, 320,
+            146, 335,
+        ];
+        naive_gemm(1, a, b, 0, c.as_mut());
+        assert_eq!(c.as_slice(), expect);
+    }
+
+    #[rustfmt::skip]
+    #[test]
+    fn fixed_2() {
+        let alpha = 3;
+        let beta = -4;
+
+        let a = [
+            1, 2, 3,
+            4, 5, 6,
+        ];
+        let b = [
+            2, 3, 4,
+            5, 6, 7,
+        ];
+        let mut c = [
+            -4, 1,
+            -5, -6,
+        ];
+        let expect = [
+            beta * c[0] + alpha * (2 + 2 * 3 + 3 * 4),
+            beta * c[1] + alpha * (5 + 2 * 6 + 3 * 7),
+            beta * c[2] + alpha * (4 * 2 + 5 * 3 + 6 * 4),
+            beta * c[3] + alpha * (4 * 5 + 5 * 6 + 6 * 7),
+        ];
+
+        let a = MatRef::row_major(2, 3, a.as_ref());
+        let b = MatRef::col_major(3, 2, b.as_ref());
+        let mut c = MatMut::row_major(2, 2, c.as_mut());
+
+        naive_gemm(alpha, a, b, beta, c.as_mut());
+        assert_eq!(c.as_slice(), expect);
+    }
+
+    #[test]
+    fn test_checked_gemm() {
+        use crate::kernels::GenericKernel4x4;
+
+        let kernel = GenericKernel4x4::<f64>::new();
+        let pack_sizes = PackSizes {
+            mc: 4,
+            kc: 2,
+            nc: 4,
+        };
+        let mut packing_buf = [0.0; 16];
+        let a = MatRef::row_major(2, 2, &[1.0, 2.0, 3.0, 4.0]);
+        let b = MatRef::col_major(2, 2, &[1.0, -1.0, 0.5, 2.0]);
+        let mut c = [f64::NAN, 1.0, 2.0, 3.0];
+        let mut c = MatMut::col_major(2, 2, &mut c);
+        let mut expect = [-1.0; 4];
+
+        let deviation = checked_gemm(
+            &kernel,
+            2.0,
+            a,
+            b,
+            0.0,
+            &mut c,
+            pack_sizes,
+            &mut packing_buf,
+            &mut expect,
+        )
+        .unwrap();
+        assert_eq!(expect, [-2.0, 9.0, -2.0, 19.0]);
+        assert_eq!(c.as_slice(), [-2.0, -2.0, 9.0, 19.0]);
+        assert_eq!(
+            deviation,
+            Deviation {
+                max_abs: 0.0,
+                max_rel: 0.0
+            }
+        );
+
+        let err = checked_gemm(
+            &kernel,
+            2.0,
+            a,
+            b,
+            0.0,
+            &mut c,
+            pack_sizes,
+            &mut packing_buf,
+            &mut expect[..3],
+        );
+        assert_eq!(
+            err,
+            Err(GemmError::BufferLen {
+                expected: 4,
+                got: 3
+            })
+        );
+    }
+
+    #[test]
+    fn test_max_or_nan() {
+        assert_eq!(max_or_nan(1.0, 2.0), 2.0);
+        assert_eq!(max_or_nan(2.0, f64::INFINITY), f64::INFINITY);
+        assert!(max_or_nan(f64::NAN, 2.0).is_nan());
+        assert!(max_or_nan(2.0, f64::NAN).is_nan());
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn fixed_3() {
+        let a = [
+            1, 0, 2,
+            0, -1, 3,
+        ];
+        let a = MatRef::row_major(2, 3, a.as_ref());
+        let b = [
+            2, -1,
+            0, 5,
+            1, 1,
+        ];
+        let b = MatRef::row_major(3, 2, b.as_ref());
+
+        let mut c = [-9; 2 * 2];
+        let c = &mut MatMut::row_major(2, 2, c.as_mut());
+        let expect = [
+            4, 1,
+            3, -2,
+        ];
+        naive_gemm(1, a, b, 0, c);
+        assert_eq!(c.as_slice(), expect);
+    }
+}
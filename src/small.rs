@@ -0,0 +1,141 @@
+use crate::gemm::tile_kernel;
+use crate::kernel::Multiply;
+use crate::packing::{pack_a, pack_b};
+use crate::{Epilogue, Kernel, MatMut, MatRef};
+use generic_array::{sequence::GenericSequence, GenericArray};
+use num_traits::{One, Zero};
+
+type Product<L, R> = <L as Multiply<R>>::Output;
+
+// Problems of at most this many `mr x nr` tiles of `c` are computed by `gemm_small`.
+const MAX_TILES: usize = 2;
+
+// Whether an `m x n` c with a positive `k` is computed by `gemm_small`.
+pub(crate) fn is_small(mr: usize, nr: usize, [m, k, n]: [usize; 3]) -> bool {
+    let tiles = ((m + mr - 1) / mr).saturating_mul((n + nr - 1) / nr);
+    k > 0 && tiles > 0 && tiles <= MAX_TILES
+}
+
+// c <- alpha a b + beta c for checked arguments of a small problem, see `is_small`,
+// without the packing buffer: the microkernel reads `a` and `b` in place if they have
+// the layout of a packed panel, otherwise chunks of their columns and rows packed into
+// stack buffers of a tile. The epilogue, if any, is applied with the last chunk.
+#[allow(clippy::too_many_arguments)]
+#[inline]
+pub(crate) fn gemm_small<T, K, E>(
+    kernel: &K,
+    alpha: T,
+    a: MatRef<T>,
+    b: MatRef<T>,
+    beta: T,
+    c: &mut MatMut<T>,
+    dst_buf: &mut [T],
+    mut epilogue: Option<&mut E>,
+) where
+    T: Copy + Zero + One,
+    K: Kernel<Scalar = T> + ?Sized,
+    E: Epilogue<T> + ?Sized,
+{
+    let [mr, nr] = [K::MR, K::NR];
+    let [m, k, n] = [a.nrows(), a.ncols(), b.ncols()];
+    debug_assert!(is_small(mr, nr, [m, k, n]));
+
+    // a single col-major panel of `mr` rows, a single row-major panel of `nr` columns
+    let direct_a = m == mr && a.row_stride() == 1 && (k == 1 || a.col_stride() == mr);
+    let direct_b = n == nr && b.col_stride() == 1 && (k == 1 || b.row_stride() == nr);
+    // a stack buffer holds `mr * nr` values, that is `nr` columns of a tile of `a`
+    // or `mr` rows of a tile of `b`
+    let chunk = match [direct_a, direct_b] {
+        [true, true] => k,
+        [true, false] => mr,
+        [false, true] => nr,
+        [false, false] => mr.min(nr),
+    };
+
+    let zero = T::zero();
+    let mut lhs_buf = GenericArray::<T, Product<K::Mr, K::Nr>>::generate(|_| zero);
+    let mut rhs_buf = GenericArray::<T, Product<K::Mr, K::Nr>>::generate(|_| zero);
+
+    for ir in (0..m).step_by(mr) {
+        for jr in (0..n).step_by(nr) {
+            let [rows, cols] = [ir..ir + mr, jr..jr + nr];
+            for p in (0..k).step_by(chunk) {
+                let depth = p..k.min(p + chunk);
+                let lhs: &[T] = if direct_a {
+                    &a.as_slice()[depth.start * mr..depth.end * mr]
+                } else {
+                    let lhs = &mut lhs_buf[..mr * depth.len()];
+                    pack_a(mr, lhs, a, rows.clone(), depth.clone());
+                    lhs
+                };
+                let rhs: &[T] = if direct_b {
+                    &b.as_slice()[depth.start * nr..depth.end * nr]
+                } else {
+                    let rhs = &mut rhs_buf[..depth.len() * nr];
+                    pack_b(nr, rhs, b, depth.clone(), cols.clone());
+                    rhs
+                };
+                let beta = if p == 0 { beta } else { One::one() };
+                let epilogue = if depth.end == k {
+                    epilogue.as_deref_mut()
+                } else {
+                    None
+                };
+                tile_kernel(
+                    kernel,
+                    alpha,
+                    lhs,
+                    rhs,
+                    beta,
+                    c,
+                    rows.clone(),
+                    cols.clone(),
+                    dst_buf,
+                    epilogue,
+                );
+            }
+        }
+    }
+}
+
+#[cfg(not(miri))]
+#[cfg(test)]
+mod proptests {
+    use super::is_small;
+    use crate::{
+        kernels::GenericKernel4x4, std_prelude::*, utils::naive_gemm, Kernel, MatMut, MatRef,
+        PackSizes,
+    };
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn proptest_gemm_small_layouts(
+            [m, n] in [1..=8usize, 1..=8],
+            k in 1..40usize,
+            [a_col_major, b_row_major, c_col_major] in any::<[bool; 3]>(),
+            alpha in -10i32..10,
+            beta in -10i32..10,
+            seed in any::<i32>(),
+        ) {
+            let kernel = GenericKernel4x4::new();
+            prop_assume!(is_small(kernel.mr(), kernel.nr(), [m, k, n]));
+
+            let values = |len: usize| (0..len).map(|x| (x as i32 ^ seed) % 10).collect::<Vec<_>>();
+            let [a, b, c] = [values(m * k), values(k * n), values(m * n)];
+            // the layouts of packed panels for m == 4 and n == 4
+            let a = if a_col_major { MatRef::col_major(m, k, &a) } else { MatRef::row_major(m, k, &a) };
+            let b = if b_row_major { MatRef::row_major(k, n, &b) } else { MatRef::col_major(k, n, &b) };
+            let c_mat = |c| if c_col_major { MatMut::col_major(m, n, c) } else { MatMut::row_major(m, n, c) };
+
+            let mut expect = c.clone();
+            naive_gemm(alpha, a, b, beta, &mut c_mat(&mut expect));
+
+            let pack_sizes = PackSizes { mc: 4, kc: 1, nc: 4 };
+            let mut packing_buf = vec![-1; pack_sizes.buf_len()];
+            let mut actual = c;
+            kernel.gemm(alpha, a, b, beta, &mut c_mat(&mut actual), pack_sizes, &mut packing_buf);
+            prop_assert_eq!(expect, actual);
+        }
+    }
+}
@@ -1,17 +1,15 @@
 use crate::mat::base::MatBase;
-use crate::{Kernel, MatRef, PackSizes};
+use crate::{Kernel, MatMut, MatRef, PackSizes};
 use proptest::prelude::*;
+use proptest::sample::SizeRange;
 use std::prelude::rust_2021::*;
 use std::{fmt, ops::RangeInclusive};
 
-#[allow(unused_imports)]
-use proptest::prelude::*;
-use proptest::sample::SizeRange;
-
-type Mat<T> = MatBase<Vec<T>, T>;
+/// Matrix that owns its values.
+pub type Mat<T> = MatBase<Vec<T>, T>;
 
 impl<T> Mat<T> {
-    pub fn to_ref<'a>(&'a self) -> MatRef<'a, T> {
+    pub fn to_ref(&self) -> MatRef<'_, T> {
         MatRef::from_parts(
             self.nrows(),
             self.ncols(),
@@ -21,18 +19,14 @@ impl<T> Mat<T> {
         )
         .unwrap()
     }
+    pub fn to_mut(&mut self) -> MatMut<'_, T> {
+        let [nrows, ncols] = [self.nrows(), self.ncols()];
+        let [row_stride, col_stride] = [self.row_stride(), self.col_stride()];
+        MatMut::from_parts(nrows, ncols, self.as_mut_slice(), row_stride, col_stride).unwrap()
+    }
 }
 
-#[macro_export]
-macro_rules! as_mut {
-    ($mat:ident) => {{
-        let [nrows, ncols] = [$mat.nrows(), $mat.ncols()];
-        let [row_stride, col_stride] = [$mat.row_stride(), $mat.col_stride()];
-        &mut $crate::MatMut::from_parts(nrows, ncols, $mat.as_mut_slice(), row_stride, col_stride)
-            .unwrap()
-    }};
-}
-
+/// Strategy for `PackSizes` within the given ranges that are valid for `K`.
 pub fn arb_pack_sizes<T, K>(
     _: &K,
     mc: impl Into<SizeRange>,
@@ -54,7 +48,8 @@ where
     .boxed()
 }
 
-#[allow(dead_code)]
+/// Strategy for `[a, b, c]` with `m x k`, `k x n` and `m x n` dimensions
+/// and either a row-major or col-major layout each.
 pub fn arb_matrix_triple<T>(
     m: impl Into<SizeRange>,
     k: impl Into<SizeRange>,
@@ -67,6 +62,7 @@ where
     arb_matrix_triple_with(m, k, n, any::<T>())
 }
 
+/// Same as [`arb_matrix_triple`], with a custom scalar strategy.
 pub fn arb_matrix_triple_with<T>(
     m: impl Into<SizeRange>,
     k: impl Into<SizeRange>,
@@ -92,6 +88,7 @@ where
         .boxed()
 }
 
+/// Strategy for a row-major or col-major matrix with arbitrary values.
 pub fn arb_matrix<T>(
     nrows: impl Into<SizeRange>,
     ncols: impl Into<SizeRange>,
@@ -0,0 +1,24 @@
+//! Property tests of [`Kernel`](crate::Kernel) implementations against a naive gemm,
+//! enabled by the `testing` feature.
+//!
+//! # Examples
+//!
+//! ```
+//! use microgemm::{kernels::GenericKernel4x4, testing::*};
+//! use proptest::strategy::Strategy;
+//!
+//! let kernel = GenericKernel4x4::<f32>::new();
+//! let cfg = ProptestKernelCfg::default()
+//!     .with_scalar((-1f32..1.0).boxed())
+//!     .with_cmp(relative_eq(16.0 * f32::EPSILON, f32::EPSILON))
+//!     .with_max_matrix_dim(12)
+//!     .with_max_pack_dim(25);
+//! proptest_kernel(&kernel, cfg).unwrap();
+//! ```
+
+mod arbitrary;
+mod proptest_kernel;
+
+pub use crate::reference::naive_gemm;
+pub use arbitrary::{arb_matrix, arb_matrix_triple, arb_matrix_triple_with, arb_pack_sizes, Mat};
+pub use proptest_kernel::{exact_eq, proptest_kernel, relative_eq, AssertEq, ProptestKernelCfg};
@@ -1,21 +1,27 @@
 use core::fmt;
 use core::ops::Mul;
 
-use crate::utils::{arb_matrix_triple_with, arb_pack_sizes, naive_gemm};
+use super::{arb_matrix_triple_with, arb_pack_sizes, naive_gemm};
+use crate::std_prelude::*;
 use crate::Kernel;
-use crate::{as_mut, std_prelude::*};
+use approx::RelativeEq;
 use proptest::sample::size_range;
 use proptest::test_runner::TestCaseResult;
 use proptest::{prelude::*, sample::SizeRange};
 
-type AssertEq<T> = dyn Fn(&[T], &[T]) -> TestCaseResult;
+/// Comparator of the expected and the actual values of `c`.
+pub type AssertEq<T> = dyn Fn(&[T], &[T]) -> TestCaseResult;
 
+/// Configuration of [`proptest_kernel`].
 pub struct ProptestKernelCfg<T> {
+    /// Ranges of `m`, `k` and `n`.
     pub mkn: [SizeRange; 3],
     pub mc: SizeRange,
     pub kc: SizeRange,
     pub nc: SizeRange,
+    /// Strategy for the values of the matrices, `alpha` and `beta`.
     pub scalar: BoxedStrategy<T>,
+    /// Comparator of `c`, [`exact_eq`] if `None`.
     pub cmp: Option<Box<AssertEq<T>>>,
 }
 
@@ -40,7 +46,6 @@ where
 }
 
 impl<T> ProptestKernelCfg<T> {
-    #[allow(dead_code)]
     pub fn with_cmp<F>(mut self, cmp: F) -> Self
     where
         F: 'static + Fn(&[T], &[T]) -> TestCaseResult,
@@ -48,18 +53,15 @@ impl<T> ProptestKernelCfg<T> {
         self.cmp = Some(Box::new(cmp));
         self
     }
-    #[allow(dead_code)]
     pub fn with_scalar(mut self, scalar: BoxedStrategy<T>) -> Self {
         self.scalar = scalar;
         self
     }
-    #[allow(dead_code)]
     pub fn with_max_matrix_dim(self, dim: usize) -> Self {
         let range = size_range(1..=dim);
         let mkn = [range.clone(), range.clone(), range.clone()];
         Self { mkn, ..self }
     }
-    #[allow(dead_code)]
     pub fn with_max_pack_dim(self, dim: usize) -> Self {
         let range = size_range(1..=dim);
         Self {
@@ -71,6 +73,64 @@ impl<T> ProptestKernelCfg<T> {
     }
 }
 
+/// Compares values with `==`.
+pub fn exact_eq<T>(expect: &[T], actual: &[T]) -> TestCaseResult
+where
+    T: fmt::Debug + PartialEq,
+{
+    prop_assert_eq!(expect.len(), actual.len());
+    for (left, right) in expect.iter().zip(actual) {
+        prop_assert_eq!(left, right);
+    }
+    Ok(())
+}
+
+/// Comparator of values whose absolute difference is at most `epsilon`
+/// or whose relative difference is at most `max_relative`, as in [`approx::relative_eq`].
+///
+/// # Examples
+///
+/// ```
+/// use microgemm::testing::{relative_eq, ProptestKernelCfg};
+///
+/// let cmp = relative_eq(75.0 * f32::EPSILON, f32::EPSILON);
+/// let cfg = ProptestKernelCfg::<f32>::default().with_cmp(cmp);
+/// ```
+pub fn relative_eq<T>(epsilon: T, max_relative: T) -> impl Fn(&[T], &[T]) -> TestCaseResult
+where
+    T: RelativeEq<Epsilon = T> + fmt::Debug + Copy,
+{
+    move |expect, actual| {
+        prop_assert_eq!(expect.len(), actual.len());
+        for (i, (&left, &right)) in expect.iter().zip(actual).enumerate() {
+            prop_assert!(
+                left.relative_eq(&right, epsilon, max_relative),
+                "{:?} != {:?} at {}, epsilon = {:?}, max_relative = {:?}",
+                left,
+                right,
+                i,
+                epsilon,
+                max_relative,
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Checks `kernel.gemm` against [`naive_gemm`] on arbitrary matrices,
+/// scalars and `PackSizes` described by `cfg`.
+///
+/// # Examples
+///
+/// ```
+/// use microgemm::{kernels::GenericKernel4x4, testing::*};
+/// use proptest::strategy::Strategy;
+///
+/// let cfg = ProptestKernelCfg::default()
+///     .with_scalar((-10i32..10).boxed())
+///     .with_max_matrix_dim(8);
+/// proptest_kernel(&GenericKernel4x4::new(), cfg).unwrap();
+/// ```
 pub fn proptest_kernel<T, K>(kernel: &K, cfg: ProptestKernelCfg<T>) -> TestCaseResult
 where
     K: Kernel<Scalar = T>,
@@ -78,16 +138,7 @@ where
 {
     let cmp = match cfg.cmp {
         Some(f) => f,
-        None => {
-            let cmp = |a: &[T], b: &[T]| -> TestCaseResult {
-                prop_assert_eq!(a.len(), b.len());
-                for (&left, &right) in a.iter().zip(b) {
-                    prop_assert_eq!(left, right);
-                }
-                Ok(())
-            };
-            Box::new(cmp)
-        }
+        None => Box::new(exact_eq),
     };
 
     let arb_pack_sizes = arb_pack_sizes(kernel, cfg.mc, cfg.kc, cfg.nc);
@@ -104,19 +155,12 @@ where
     )| {
         let [a, b] = [a.to_ref(), b.to_ref()];
         let mut expect = c.clone();
-        naive_gemm(alpha, a, b, beta, as_mut!(expect));
+        naive_gemm(alpha, a, b, beta, &mut expect.to_mut());
 
         proptest!(|(pack_sizes in arb_pack_sizes.clone())| {
             let mut actual = c.clone();
-            kernel.gemm_in(
-                crate::GlobalAllocator,
-                alpha,
-                a,
-                b,
-                beta,
-                as_mut!(actual),
-                pack_sizes,
-            );
+            let mut packing_buf = vec![T::zero(); pack_sizes.buf_len()];
+            kernel.gemm(alpha, a, b, beta, &mut actual.to_mut(), pack_sizes, &mut packing_buf);
             cmp(expect.as_slice(), actual.as_slice())?;
         });
     });
@@ -1,13 +1,21 @@
-mod arbitrary;
-mod naive;
-
-mod proptest_kernel;
-
+use crate::testing::{relative_eq, ProptestKernelCfg};
 use approx::{AbsDiffEq, RelativeEq};
+use core::ops::Range;
+use num_traits::float::FloatCore;
+use proptest::{arbitrary::Arbitrary, strategy::Strategy};
+
+pub use crate::reference::naive_gemm;
+pub use crate::testing::{arb_matrix, arb_matrix_triple_with};
 
-pub use arbitrary::*;
-pub use naive::naive_gemm;
-pub use proptest_kernel::{proptest_kernel, ProptestKernelCfg};
+#[macro_export]
+macro_rules! as_mut {
+    ($mat:ident) => {{
+        let [nrows, ncols] = [$mat.nrows(), $mat.ncols()];
+        let [row_stride, col_stride] = [$mat.row_stride(), $mat.col_stride()];
+        &mut $crate::MatMut::from_parts(nrows, ncols, $mat.as_mut_slice(), row_stride, col_stride)
+            .unwrap()
+    }};
+}
 
 pub fn assert_approx_eq<T>(left: impl AsRef<[T]>, right: impl AsRef<[T]>, eps: T)
 where
@@ -22,6 +30,28 @@ where
     }
 }
 
+// Configuration of the proptests of the simd float kernels.
+#[allow(dead_code)]
+pub fn simd_cfg<T>() -> ProptestKernelCfg<T>
+where
+    T: FloatCore + RelativeEq<Epsilon = T> + Arbitrary + core::fmt::Debug + 'static,
+    Range<T>: Strategy<Value = T>,
+{
+    let dim = 80;
+    let [one, eps] = [T::one(), T::epsilon()];
+    ProptestKernelCfg::default()
+        .with_cmp(relative_eq(T::from(75).unwrap() * eps, eps))
+        .with_scalar((-one..one).boxed())
+        .with_max_matrix_dim(dim)
+        .with_max_pack_dim(2 * dim + 1)
+}
+
+// Reports a test skipped at runtime, e.g. for missing cpu features.
+#[allow(dead_code)]
+pub fn skip_test(reason: &str) {
+    eprintln!("test skipped: {reason}");
+}
+
 #[allow(dead_code)]
 pub const fn is_release_build() -> bool {
     !is_debug_build()
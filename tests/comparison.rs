@@ -29,6 +29,41 @@ fn bench_aarch64_f32() {
     }
 }
 
+#[ignore]
+#[test]
+#[cfg(target_arch = "x86_64")]
+fn bench_x86_64_f32() {
+    use microgemm::kernels::{Avx2FmaKernel8x8, Sse2Kernel4x4};
+
+    let avx2_kernel = if is_x86_feature_detected!("avx2") && is_x86_feature_detected!("fma") {
+        Some(unsafe { Avx2FmaKernel8x8::<f32>::new() })
+    } else {
+        println!("avx2 and fma features are not supported, skipping Avx2FmaKernel8x8...");
+        None
+    };
+    let sse2_kernel = Sse2Kernel4x4::<f32>::new();
+    let mt_kernel = MatrixMultiplyKernel;
+    let faer_kernel = FaerKernel;
+
+    const TRIES: u32 = 6;
+
+    let sizes = (7..12).map(|x| 2usize.pow(x));
+    println!(
+        "{0:>4} {1:>16} {2:>14} {3:>14} {4:>14}",
+        "n", "Avx2FmaKernel8x8", "Sse2Kernel4x4", "faer", "matrixmultiply",
+    );
+    for n in sizes {
+        let t_avx2 = match avx2_kernel {
+            Some(ref kernel) => display_duration(time_with(kernel, n, TRIES)),
+            None => "-".to_string(),
+        };
+        let t_sse2 = display_duration(time_with(&sse2_kernel, n, TRIES));
+        let t_faer = display_duration(time_with(&faer_kernel, n, TRIES));
+        let t_mt = display_duration(time_with(&mt_kernel, n, TRIES));
+        println!("{n:>4} {t_avx2:>16} {t_sse2:>14} {t_faer:>14} {t_mt:>14}");
+    }
+}
+
 #[allow(dead_code)]
 fn display_duration(t: Duration) -> String {
     let as_float = |s: &str, unit: &str| {
@@ -0,0 +1,92 @@
+#![cfg(target_arch = "x86_64")]
+
+use microgemm::{
+    kernels::{Avx2FmaKernel8x8, Sse2Kernel4x4},
+    Kernel, MatMut, MatRef, PackSizes,
+};
+
+#[test]
+fn test_avx2_fma_8x8() {
+    let kernel = if is_x86_feature_detected!("avx2") && is_x86_feature_detected!("fma") {
+        unsafe { Avx2FmaKernel8x8::<f32>::new() }
+    } else {
+        println!("avx2 and fma features are not supported");
+        return;
+    };
+    test_kernel_f32(kernel);
+}
+
+#[test]
+fn test_sse2_4x4() {
+    test_kernel_f32(Sse2Kernel4x4::<f32>::new());
+}
+
+fn test_kernel_f32(kernel: impl Kernel<Scalar = f32>) {
+    let pack_sizes = PackSizes {
+        mc: kernel.mr(),
+        kc: 2,
+        nc: kernel.nr(),
+    };
+
+    let test_cases = vec![
+        TestCase {
+            alpha: 1.,
+            a: MatRef::row_major(2, 3, &[1., 2., 3., 4., 5., 6.]),
+            b: MatRef::row_major(3, 2, &[10., 11., 20., 21., 30., 31.]),
+            c: MatRef::row_major(2, 2, &[99.; 2 * 2]),
+            beta: 0.,
+            expect: &[140., 146., 320., 335.],
+            pack_sizes,
+        },
+        TestCase {
+            alpha: 0.,
+            a: MatRef::row_major(2, 2, &[1., 2., 3., 4.]),
+            b: MatRef::row_major(2, 2, &[5., 6., 3., 4.]),
+            c: MatRef::row_major(2, 2, &[3.; 2 * 2]),
+            beta: 1.,
+            expect: &[3., 3., 3., 3.],
+            pack_sizes,
+        },
+    ];
+
+    for test_case in test_cases {
+        test_case.test_kernel(&kernel);
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct TestCase<'a> {
+    alpha: f32,
+    a: MatRef<'a, f32>,
+    b: MatRef<'a, f32>,
+    beta: f32,
+    c: MatRef<'a, f32>,
+    pack_sizes: PackSizes,
+    expect: &'a [f32],
+}
+
+impl<'a> TestCase<'a> {
+    fn test_kernel(&self, kernel: &impl Kernel<Scalar = f32>) {
+        let param = self;
+        let mut actual = param.c.as_slice().to_vec();
+        let mut actual = MatMut::from_parts(
+            param.c.nrows(),
+            param.c.ncols(),
+            &mut actual,
+            param.c.row_stride(),
+            param.c.col_stride(),
+        )
+        .unwrap();
+        let mut packing_buf = vec![0f32; param.pack_sizes.buf_len()];
+        kernel.gemm(
+            param.alpha,
+            param.a,
+            param.b,
+            param.beta,
+            &mut actual,
+            param.pack_sizes,
+            &mut packing_buf,
+        );
+        assert_eq!(actual.as_slice(), param.expect, "{self:?}");
+    }
+}